@@ -0,0 +1,41 @@
+//! Benchmarks of the parser and of both parts of every day, against the real inputs. Days whose
+//! input is missing are skipped.
+//!
+//! Run a single day with `cargo bench -- day13`, or a single step with `cargo bench -- day13/parse`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use aoc2022::{input::InputDirs, solution::Part, DAYS};
+
+fn bench_days(c: &mut Criterion) {
+    let dirs = InputDirs::from_env();
+    for solution in DAYS {
+        let day = solution.day();
+        let data = match dirs.read(day) {
+            Ok(data) => data,
+            Err(e) => {
+                eprintln!("Skipping day {day}: {e}");
+                continue;
+            }
+        };
+        let parsed = match solution.parse(&data) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("Skipping day {day}: {e:#}");
+                continue;
+            }
+        };
+
+        let mut group = c.benchmark_group(format!("day{day:02}"));
+        group.bench_function("parse", |b| b.iter(|| solution.parse(black_box(&data))));
+        for part in Part::ALL {
+            group.bench_function(format!("part{part}"), |b| {
+                b.iter(|| parsed.solve(black_box(part)))
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_days);
+criterion_main!(benches);
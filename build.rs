@@ -0,0 +1,44 @@
+//! Generates a test for each part of each example listed in `examples/answers.toml`, to be
+//! included by `tests/examples.rs`.
+
+use std::{fmt::Write, path::Path};
+
+const EXAMPLES_DIR: &str = "examples";
+
+fn main() {
+    let answers_file = Path::new(EXAMPLES_DIR).join("answers.toml");
+    println!("cargo:rerun-if-changed={EXAMPLES_DIR}");
+    println!("cargo:rerun-if-changed={}", answers_file.display());
+
+    let answers = std::fs::read_to_string(&answers_file)
+        .unwrap_or_default()
+        .parse::<toml::Table>()
+        .expect("Invalid examples/answers.toml");
+
+    let mut tests = String::new();
+    for (name, parts) in &answers {
+        let example_file = Path::new(EXAMPLES_DIR).join(format!("{name}.txt"));
+        if !example_file.exists() {
+            println!(
+                "cargo:warning=No example file {} for the answers in {}",
+                example_file.display(),
+                answers_file.display()
+            );
+            continue;
+        }
+
+        let ident = name.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+        for (key, part) in [("part1", "One"), ("part2", "Two")] {
+            if parts.get(key).is_some() {
+                writeln!(
+                    tests,
+                    "#[test]\nfn {ident}_{key}() {{\n    check_example({name:?}, Part::{part});\n}}\n"
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    std::fs::write(Path::new(&out_dir).join("example_tests.rs"), tests).unwrap();
+}
@@ -0,0 +1,15 @@
+//! Every day's parser must return an error rather than panic, whatever the input.
+//!
+//! Run with `cargo +nightly fuzz run all_days`, or `day05`, `day07`... to focus on one parser.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(data) = std::str::from_utf8(data) {
+        for solution in aoc2022::DAYS {
+            let _ = solution.parse(data);
+        }
+    }
+});
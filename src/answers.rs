@@ -0,0 +1,130 @@
+use std::{collections::BTreeMap, fmt::Display, path::Path};
+
+use eyre::{Result, WrapErr};
+use serde::Deserialize;
+
+use crate::solution::{Answer, Part};
+
+/// Default location of the file recording the accepted answers
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// Location of the file recording the answers to the puzzle examples in `examples/`
+pub const EXAMPLE_ANSWERS_FILE: &str = "examples/answers.toml";
+
+/// The accepted answers of each day, as recorded in `answers.toml`:
+///
+/// ```toml
+/// [day01]
+/// part1 = 72240
+/// part2 = 210957
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct ExpectedAnswers(BTreeMap<String, DayAnswers>);
+
+#[derive(Debug, Default, Deserialize)]
+struct DayAnswers {
+    part1: Option<Answer>,
+    part2: Option<Answer>,
+}
+
+impl ExpectedAnswers {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read answers from {}", path.display()))?;
+        data.parse()
+            .wrap_err_with(|| format!("Invalid answers file {}", path.display()))
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&Answer> {
+        self.get_named(&format!("day{day:02}"), part)
+    }
+
+    /// Look up an answer by the name of its section, e.g. `day09-larger` for the answers to
+    /// `examples/day09-larger.txt`
+    pub fn get_named(&self, name: &str, part: Part) -> Option<&Answer> {
+        let answers = self.0.get(name)?;
+        match part {
+            Part::One => answers.part1.as_ref(),
+            Part::Two => answers.part2.as_ref(),
+        }
+    }
+
+    pub fn verify(&self, day: u8, part: Part, actual: &Answer) -> Verdict {
+        match self.get(day, part) {
+            Some(expected) if expected == actual => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.clone(),
+            },
+            None => Verdict::Missing,
+        }
+    }
+}
+
+impl std::str::FromStr for ExpectedAnswers {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(toml::from_str(s)?)
+    }
+}
+
+/// The outcome of comparing a computed answer with the recorded one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: Answer },
+    Missing,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { .. } => write!(f, "FAIL"),
+            Verdict::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify() {
+        let answers = r#"
+[day05]
+part1 = "CMZ"
+
+[day11]
+part1 = 10605
+part2 = 2713310158
+"#
+        .parse::<ExpectedAnswers>()
+        .unwrap();
+
+        assert_eq!(
+            answers.verify(5, Part::One, &Answer::from("CMZ")),
+            Verdict::Pass
+        );
+        assert_eq!(
+            answers.verify(5, Part::Two, &Answer::from("MCD")),
+            Verdict::Missing
+        );
+        assert_eq!(
+            answers.verify(11, Part::Two, &Answer::Integer(2713310158)),
+            Verdict::Pass
+        );
+        assert_eq!(
+            answers.verify(11, Part::One, &Answer::Integer(10604)),
+            Verdict::Fail {
+                expected: Answer::Integer(10605)
+            }
+        );
+        assert_eq!(
+            answers.verify(1, Part::One, &Answer::Integer(1)),
+            Verdict::Missing
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use std::{path::PathBuf, str::FromStr};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use eyre::{bail, eyre, Report, Result};
+
+use aoc2022::{answers::ANSWERS_FILE, input::InputSource, solution::Part, submit::HISTORY_FILE};
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Advent of Code 2022 solutions")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the solutions for the selected days
+    Run {
+        /// Days to run: a single day (`7`), a range (`1..=5` or `1..6`) or `all`
+        #[arg(default_value = "all")]
+        days: DaySelection,
+        /// Only run the given part (1 or 2)
+        #[arg(long)]
+        part: Option<Part>,
+        /// Read the input from this file (or `-` for stdin) instead of looking it up in
+        /// `$AOC_INPUT_DIR`, `inputs/` or the per-user input directory
+        #[arg(long, short)]
+        input: Option<InputSource>,
+        /// How to print the results
+        #[arg(long, value_enum, default_value_t = Format::Text, conflicts_with = "visualize")]
+        format: Format,
+        /// Number of threads to solve the days on (0 for one per CPU)
+        #[arg(long, short, default_value_t = 1, conflicts_with = "visualize")]
+        jobs: usize,
+        #[command(flatten)]
+        view: Visualize,
+    },
+    /// Repeatedly run the solutions for the selected days and report how long each step takes
+    Bench {
+        /// Days to benchmark: a single day (`7`), a range (`1..=5` or `1..6`) or `all`
+        #[arg(default_value = "all")]
+        days: DaySelection,
+        /// Only benchmark the given part (1 or 2)
+        #[arg(long)]
+        part: Option<Part>,
+        /// Read the input from this file (or `-` for stdin)
+        #[arg(long, short)]
+        input: Option<InputSource>,
+        /// Number of times each step is run
+        #[arg(long, short = 'n', default_value_t = 10)]
+        iterations: usize,
+    },
+    /// Check the answers of the selected days against the recorded ones
+    Verify {
+        /// Days to verify: a single day (`7`), a range (`1..=5` or `1..6`) or `all`
+        #[arg(default_value = "all")]
+        days: DaySelection,
+        /// File containing the accepted answers
+        #[arg(long, default_value = ANSWERS_FILE)]
+        answers: PathBuf,
+        /// Number of threads to solve the days on (0 for one per CPU)
+        #[arg(long, short, default_value_t = 1)]
+        jobs: usize,
+    },
+    /// Download the inputs of the selected days that are not already present, using the session
+    /// token in `$AOC_SESSION`
+    Fetch {
+        /// Days to download: a single day (`7`), a range (`1..=5` or `1..6`) or `all`
+        days: DaySelection,
+        /// URL of the event on the website, defaults to `$AOC_BASE_URL` or
+        /// https://adventofcode.com/2022
+        #[arg(long)]
+        base_url: Option<String>,
+    },
+    /// Submit the answer of a part to the website, unless earlier attempts show it is wrong, and
+    /// record the verdict
+    Submit {
+        /// The day of the answer, between 1 and 25
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// The part of the answer (1 or 2)
+        part: Part,
+        /// Submit this answer instead of computing it
+        #[arg(long)]
+        answer: Option<String>,
+        /// URL of the event on the website, defaults to `$AOC_BASE_URL` or
+        /// https://adventofcode.com/2022
+        #[arg(long)]
+        base_url: Option<String>,
+        /// File recording the submitted answers
+        #[arg(long, default_value = HISTORY_FILE)]
+        history: PathBuf,
+    },
+    /// Save a picture of the state of a day once solved (days 8, 9, 10 and 12) as a PNG or SVG
+    /// image
+    Export {
+        /// The day to export, between 1 and 25
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Image file to write, whose extension (.png or .svg) gives the format
+        output: PathBuf,
+        /// The part whose state to export (1 or 2)
+        #[arg(long, default_value = "1")]
+        part: Part,
+        /// Read the input from this file (or `-` for stdin)
+        #[arg(long, short)]
+        input: Option<InputSource>,
+        /// Size in pixels of each cell of the picture
+        #[arg(long, default_value_t = 8, value_parser = clap::value_parser!(u64).range(1..=100))]
+        scale: u64,
+    },
+    /// Run a day and its tests again each time its source, input or examples change, showing how
+    /// the answers changed
+    Watch {
+        /// The day to watch, between 1 and 25
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+    },
+    /// Generate the module of a new day, register it and create its input and example files
+    New {
+        /// The day to create, between 1 and 25
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+    },
+    /// Print a random input for a day, to stress-test or benchmark its solution
+    Gen {
+        /// The day to generate an input for, between 1 and 25
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Seed of the random generator: the same seed and parameters give the same input
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Number of elves (day 1), rounds (day 2), rucksacks (day 3), pairs (days 4 and 13), moves
+        /// (days 5 and 9), characters (day 6), directories (day 7), instructions (day 10) or
+        /// monkeys (day 11)
+        #[arg(long, short = 'n')]
+        size: Option<usize>,
+        /// Width of the grid (days 8 and 12) or number of stacks (day 5)
+        #[arg(long)]
+        width: Option<usize>,
+        /// Height of the grid (days 8 and 12) or maximum height of the starting stacks (day 5)
+        #[arg(long)]
+        height: Option<usize>,
+        /// Maximum nesting depth of the packets (day 13)
+        #[arg(long)]
+        depth: Option<usize>,
+        /// Number of rounds the monkeys must be able to play with relief (day 11)
+        #[arg(long)]
+        rounds: Option<usize>,
+    },
+}
+
+/// Options of `run` showing the simulation of a day instead of its answers
+#[derive(Debug, Args)]
+pub struct Visualize {
+    /// Show the simulation of a single day step by step instead (days 5, 9, 10 and 12), for the
+    /// given part or part 1
+    #[arg(long)]
+    pub visualize: bool,
+    /// Frames shown per second when visualising, or played per second in the GIF
+    #[arg(long, default_value_t = 10.0, requires = "visualize")]
+    pub fps: f64,
+    /// Write all the frames of the visualisation to this text file instead of showing them
+    #[arg(long, requires = "visualize", conflicts_with = "gif")]
+    pub frames: Option<PathBuf>,
+    /// Record the visualisation as an animated GIF in this file instead of showing it
+    #[arg(long, requires = "visualize")]
+    pub gif: Option<PathBuf>,
+    /// Number of frames to leave out of the GIF after each one recorded
+    #[arg(long, default_value_t = 0, requires = "gif")]
+    pub skip: usize,
+    /// Size in pixels of each character of the frames in the GIF
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u64).range(1..=100), requires = "gif")]
+    pub scale: u64,
+}
+
+/// Output format of the results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Human readable answers followed by a table of timings
+    Text,
+    /// One JSON object per line and per day/part
+    Json,
+}
+
+/// The set of days selected on the command line, in ascending order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaySelection(Vec<u8>);
+
+impl DaySelection {
+    pub const FIRST_DAY: u8 = 1;
+    pub const LAST_DAY: u8 = 25;
+
+    pub fn all() -> Self {
+        Self((Self::FIRST_DAY..=Self::LAST_DAY).collect())
+    }
+
+    pub fn days(&self) -> &[u8] {
+        &self.0
+    }
+
+    pub fn is_all(&self) -> bool {
+        *self == Self::all()
+    }
+}
+
+impl FromStr for DaySelection {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "all" {
+            return Ok(Self::all());
+        }
+
+        let (first, last) = if let Some((first, last)) = s.split_once("..=") {
+            (
+                parse_day(first, Self::LAST_DAY)?,
+                parse_day(last, Self::LAST_DAY)?,
+            )
+        } else if let Some((first, end)) = s.split_once("..") {
+            // The end is excluded, so it can be the day after the last one
+            let end = parse_day(end, Self::LAST_DAY + 1)?;
+            if end == Self::FIRST_DAY {
+                bail!("Invalid day range {s:?}: it is empty");
+            }
+            (parse_day(first, Self::LAST_DAY)?, end - 1)
+        } else {
+            let day = parse_day(s, Self::LAST_DAY)?;
+            (day, day)
+        };
+
+        if first > last {
+            bail!("Invalid day range {s:?}: it is empty");
+        }
+        Ok(Self((first..=last).collect()))
+    }
+}
+
+/// Parse a day between the first one and `last`
+fn parse_day(s: &str, last: u8) -> Result<u8> {
+    let day = s
+        .trim()
+        .parse::<u8>()
+        .map_err(|_| eyre!("Invalid day {s:?}"))?;
+    if !(DaySelection::FIRST_DAY..=last).contains(&day) {
+        bail!(
+            "Invalid day {day}: must be between {} and {last}",
+            DaySelection::FIRST_DAY
+        );
+    }
+    Ok(day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_selection() {
+        assert_eq!("7".parse::<DaySelection>().unwrap().days(), &[7]);
+        assert_eq!(
+            "1..=5".parse::<DaySelection>().unwrap().days(),
+            &[1, 2, 3, 4, 5]
+        );
+        assert_eq!(
+            "1..5".parse::<DaySelection>().unwrap().days(),
+            &[1, 2, 3, 4]
+        );
+        assert_eq!(
+            "20..26".parse::<DaySelection>().unwrap().days(),
+            &[20, 21, 22, 23, 24, 25]
+        );
+        assert!("1..26".parse::<DaySelection>().unwrap().is_all());
+        assert!("all".parse::<DaySelection>().unwrap().is_all());
+
+        assert!("0".parse::<DaySelection>().is_err());
+        assert!("26".parse::<DaySelection>().is_err());
+        assert!("5..=1".parse::<DaySelection>().is_err());
+        assert!("3..3".parse::<DaySelection>().is_err());
+        assert!("1..27".parse::<DaySelection>().is_err());
+        assert!("1..=26".parse::<DaySelection>().is_err());
+        assert!("foo".parse::<DaySelection>().is_err());
+    }
+
+    #[test]
+    fn test_visualize_conflicts() {
+        let parse = |args: &str| Cli::try_parse_from(args.split(' '));
+        assert!(parse("aoc2022 run 9 --visualize --frames frames.txt").is_ok());
+        assert!(parse("aoc2022 run 9 --visualize --format json").is_err());
+        assert!(parse("aoc2022 run 9 --visualize --jobs 4").is_err());
+        assert!(parse("aoc2022 run 9 --visualize -j 4").is_err());
+        assert!(parse("aoc2022 run all --format json --jobs 4").is_ok());
+    }
+}
@@ -1,29 +1,76 @@
-use std::num::ParseIntError;
+use eyre::{ContextCompat, Result};
 
-use eyre::Result;
+use crate::{
+    parse::{Lines, ParseError},
+    solution::{Answer, Solution},
+};
 
-pub fn run() -> Result<()> {
-    let data = std::fs::read_to_string("inputs/day01.txt")?;
+pub struct Day01;
 
-    let (mut calories, _) =
-        data.lines()
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<u64>;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        Ok(parse_calories(data)?)
+    }
+
+    fn part1(calories: &Self::Input) -> Result<Answer> {
+        let max = calories.iter().max().context("No elves")?;
+        (*max).try_into()
+    }
+
+    fn part2(calories: &Self::Input) -> Result<Answer> {
+        let mut calories = calories.clone();
+        calories.sort();
+        let top_3_sum = calories
             .into_iter()
-            .try_fold((Vec::new(), 0), |(mut vec, acc), line| {
-                if line.trim().is_empty() {
-                    vec.push(acc);
-                    Ok::<_, ParseIntError>((vec, 0))
-                } else {
-                    let cal = line.trim().parse::<u64>()?;
-                    Ok((vec, acc + cal))
-                }
-            })?;
-
-    let max = calories.iter().max().expect("empty vector?");
-    println!("Elf with most calories carries {max} calories");
-
-    calories.sort();
-    let top_3_sum: u64 = calories.into_iter().rev().take(3).sum();
-    println!("Top 3 elves carrying the most calories carry {top_3_sum} calories in total");
-
-    Ok(())
+            .rev()
+            .take(3)
+            .try_fold(0u64, u64::checked_add)
+            .context("The calories of the top 3 elves overflow")?;
+        top_3_sum.try_into()
+    }
+}
+
+/// Parse the list of calories carried by each elf into the total carried by each elf
+pub fn parse_calories(data: &str) -> Result<Vec<u64>, ParseError> {
+    let (mut calories, last) =
+        Lines::new(Day01::DAY, data).try_fold((Vec::new(), 0u64), |(mut vec, acc), line| {
+            let mut s = line.scanner();
+            s.take_while(char::is_whitespace);
+            if s.is_at_end() {
+                vec.push(acc);
+                Ok((vec, 0))
+            } else {
+                let pos = s.pos();
+                let cal = s.number::<u64>("a number of calories")?;
+                let total = acc.checked_add(cal).ok_or_else(|| {
+                    line.error_at(pos, "a number of calories keeping the total below 2^64")
+                })?;
+                s.take_while(char::is_whitespace);
+                s.end()?;
+                Ok((vec, total))
+            }
+        })?;
+    // The last elf isn't followed by an empty line
+    calories.push(last);
+
+    Ok(calories)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overflow() {
+        let e = parse_calories("18446744073709551615\n1\n").unwrap_err();
+        assert_eq!((e.line, e.column), (2, 1));
+
+        let calories = parse_calories("18446744073709551615\n\n1\n").unwrap();
+        assert_eq!(calories, [u64::MAX, 1]);
+        assert!(Day01::part2(&calories).is_err());
+    }
 }
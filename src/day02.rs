@@ -2,15 +2,20 @@ use std::cmp::Ordering;
 
 use eyre::{eyre, Report, Result};
 
+use crate::{
+    parse::{Line, Lines, ParseError},
+    solution::{Answer, Solution},
+};
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum Shape {
+pub enum Shape {
     Rock,
     Paper,
     Scissors,
 }
 
 impl Shape {
-    fn score(&self) -> u64 {
+    pub fn score(&self) -> u64 {
         match self {
             Shape::Rock => 1,
             Shape::Paper => 2,
@@ -18,7 +23,8 @@ impl Shape {
         }
     }
 
-    fn chose_shape(&self, outcome: Outcome) -> Shape {
+    /// The shape to play against `self` to get the given outcome
+    pub fn chose_shape(&self, outcome: Outcome) -> Shape {
         match (self, outcome) {
             (Shape::Rock, Outcome::Lose) => Shape::Scissors,
             (Shape::Rock, Outcome::Draw) => Shape::Rock,
@@ -34,8 +40,14 @@ impl Shape {
 }
 
 impl PartialOrd for Shape {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        let res = match (self, other) {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Shape {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
             (Shape::Rock, Shape::Rock) => Ordering::Equal,
             (Shape::Rock, Shape::Paper) => Ordering::Less,
             (Shape::Rock, Shape::Scissors) => Ordering::Greater,
@@ -45,14 +57,7 @@ impl PartialOrd for Shape {
             (Shape::Scissors, Shape::Rock) => Ordering::Less,
             (Shape::Scissors, Shape::Paper) => Ordering::Greater,
             (Shape::Scissors, Shape::Scissors) => Ordering::Equal,
-        };
-        Some(res)
-    }
-}
-
-impl Ord for Shape {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).expect("Impossible")
+        }
     }
 }
 
@@ -69,7 +74,8 @@ impl TryFrom<u8> for Shape {
     }
 }
 
-struct Round(Shape, Shape);
+/// A round of the game: the opponent's shape, then ours
+pub struct Round(pub Shape, pub Shape);
 
 impl Round {
     pub fn score(&self) -> u64 {
@@ -102,43 +108,60 @@ impl TryFrom<u8> for Outcome {
     }
 }
 
-pub fn run() -> Result<()> {
-    let data = std::fs::read_to_string("inputs/day02.txt")?;
-    let letters = data
-        .lines()
-        .map(|line| {
-            let bytes = line.as_bytes();
-            let shape1 = bytes[0];
-            let shape2 = bytes[2];
-            (shape1, shape2)
-        })
-        .collect::<Vec<_>>();
-
-    let rounds = letters
-        .iter()
-        .map(|(a, b)| {
-            let shape1 = Shape::try_from(*a)?;
-            let shape2 = Shape::try_from(*b)?;
-            Ok(Round(shape1, shape2))
-        })
-        .collect::<Result<Vec<Round>>>()?;
-
-    let total_score = rounds.iter().map(|r| r.score()).sum::<u64>();
-    println!("Total score: {total_score}");
-
-    let rounds = letters
-        .iter()
-        .map(|(a, b)| {
-            let theirs = Shape::try_from(*a)?;
-            let outcome = Outcome::try_from(*b)?;
-            let ours = theirs.chose_shape(outcome);
-
-            Ok(Round(theirs, ours))
-        })
-        .collect::<Result<Vec<Round>>>()?;
-
-    let total_score = rounds.iter().map(|r| r.score()).sum::<u64>();
-    println!("Total score: {total_score}");
-
-    Ok(())
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<(u8, u8)>;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        Ok(parse_letters(data)?)
+    }
+
+    fn part1(letters: &Self::Input) -> Result<Answer> {
+        let rounds = letters
+            .iter()
+            .map(|(a, b)| {
+                let shape1 = Shape::try_from(*a)?;
+                let shape2 = Shape::try_from(*b)?;
+                Ok(Round(shape1, shape2))
+            })
+            .collect::<Result<Vec<Round>>>()?;
+
+        let total_score = rounds.iter().map(|r| r.score()).sum::<u64>();
+        total_score.try_into()
+    }
+
+    fn part2(letters: &Self::Input) -> Result<Answer> {
+        let rounds = letters
+            .iter()
+            .map(|(a, b)| {
+                let theirs = Shape::try_from(*a)?;
+                let outcome = Outcome::try_from(*b)?;
+                let ours = theirs.chose_shape(outcome);
+
+                Ok(Round(theirs, ours))
+            })
+            .collect::<Result<Vec<Round>>>()?;
+
+        let total_score = rounds.iter().map(|r| r.score()).sum::<u64>();
+        total_score.try_into()
+    }
+}
+
+/// Parse the two letters of each line of the strategy guide
+pub fn parse_letters(data: &str) -> Result<Vec<(u8, u8)>, ParseError> {
+    Lines::new(Day02::DAY, data)
+        .map(|line| parse_round(&line))
+        .collect()
+}
+
+fn parse_round(line: &Line) -> Result<(u8, u8), ParseError> {
+    let mut s = line.scanner();
+    let theirs = s.one_of("ABC")?;
+    s.tag(" ")?;
+    let ours = s.one_of("XYZ")?;
+    s.end()?;
+    Ok((theirs as u8, ours as u8))
 }
@@ -1,50 +1,93 @@
 use std::collections::HashSet;
 
-use eyre::Result;
-
-pub fn run() -> Result<()> {
-    let data = std::fs::read_to_string("inputs/day03.txt")?;
-
-    let mut common_items = Vec::new();
-    for line in data.lines() {
-        let bytes = line.as_bytes();
-        let (a, b) = bytes.split_at(bytes.len() / 2);
-        let set_a: HashSet<u8> = HashSet::from_iter(a.iter().copied());
-        let set_b: HashSet<u8> = HashSet::from_iter(b.iter().copied());
-        let common = set_a.intersection(&set_b).next().unwrap();
-        common_items.push(*common);
+use eyre::{bail, ContextCompat, Result};
+
+use crate::{
+    parse::{Line, Lines, ParseError},
+    solution::{Answer, Solution},
+};
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+
+    type Input = Vec<String>;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        Ok(Lines::new(Self::DAY, data)
+            .map(|line| parse_rucksack(&line))
+            .collect::<Result<_, _>>()?)
     }
 
-    let sum = common_items.into_iter().map(priority).sum::<u64>();
-    println!("Sum of priorities: {sum}");
+    fn part1(rucksacks: &Self::Input) -> Result<Answer> {
+        let mut common_items = Vec::new();
+        for line in rucksacks {
+            let bytes = line.as_bytes();
+            let (a, b) = bytes.split_at(bytes.len() / 2);
+            let set_a: HashSet<u8> = HashSet::from_iter(a.iter().copied());
+            let set_b: HashSet<u8> = HashSet::from_iter(b.iter().copied());
+            let common = set_a
+                .intersection(&set_b)
+                .next()
+                .with_context(|| format!("No item in both compartments of {line}"))?;
+            common_items.push(*common);
+        }
 
-    let mut badges = Vec::new();
-    let lines = data.lines().collect::<Vec<_>>();
+        let sum = common_items
+            .into_iter()
+            .map(priority)
+            .sum::<Result<u64>>()?;
+        sum.try_into()
+    }
 
-    for group in lines.chunks_exact(3) {
-        let set_a: HashSet<u8> = HashSet::from_iter(group[0].as_bytes().iter().copied());
-        let set_b = HashSet::from_iter(group[1].as_bytes().iter().copied());
-        let set_c = HashSet::from_iter(group[2].as_bytes().iter().copied());
+    fn part2(rucksacks: &Self::Input) -> Result<Answer> {
+        if !rucksacks.len().is_multiple_of(3) {
+            bail!(
+                "The {} rucksacks can't be split in groups of 3",
+                rucksacks.len()
+            );
+        }
 
-        let intersection_a_b = set_a.intersection(&set_b).copied().collect::<HashSet<_>>();
-        let intersection = intersection_a_b
-            .intersection(&set_c)
-            .copied()
-            .next()
-            .unwrap();
+        let mut badges = Vec::new();
 
-        badges.push(intersection);
+        for group in rucksacks.chunks_exact(3) {
+            let set_a: HashSet<u8> = HashSet::from_iter(group[0].as_bytes().iter().copied());
+            let set_b = HashSet::from_iter(group[1].as_bytes().iter().copied());
+            let set_c = HashSet::from_iter(group[2].as_bytes().iter().copied());
+
+            let intersection_a_b = set_a.intersection(&set_b).copied().collect::<HashSet<_>>();
+            let intersection = intersection_a_b
+                .intersection(&set_c)
+                .copied()
+                .next()
+                .with_context(|| format!("No badge in common in group {}", group.join(", ")))?;
+
+            badges.push(intersection);
+        }
+        let sum = badges.into_iter().map(priority).sum::<Result<u64>>()?;
+        sum.try_into()
     }
-    let sum = badges.into_iter().map(priority).sum::<u64>();
-    println!("Sum of badges priorities: {sum}");
+}
 
-    Ok(())
+/// Check that a rucksack holds items (letters) split evenly between its two compartments
+pub fn parse_rucksack(line: &Line) -> Result<String, ParseError> {
+    let mut s = line.scanner();
+    s.take_while(|c| c.is_ascii_alphabetic());
+    if !s.is_at_end() {
+        return Err(s.error("an item (a-z or A-Z)"));
+    }
+    if !line.text.len().is_multiple_of(2) {
+        return Err(s.error("an even number of items"));
+    }
+    Ok(line.text.to_string())
 }
 
-fn priority(item: u8) -> u64 {
-    (match item {
+pub fn priority(item: u8) -> Result<u64> {
+    let priority = match item {
         b'a'..=b'z' => item - b'a' + 1,
         b'A'..=b'Z' => item - b'A' + 27,
-        _ => panic!("Invalid item"),
-    }) as u64
+        _ => bail!("Invalid item {:?}", item as char),
+    };
+    Ok(priority as u64)
 }
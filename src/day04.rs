@@ -1,44 +1,63 @@
 use std::ops::RangeInclusive;
 
-use eyre::{ContextCompat, Result};
-
-pub fn run() -> Result<()> {
-    let data = std::fs::read_to_string("inputs/day04.txt")?;
-
-    let range_pairs = data.lines().map(parse_ranges).collect::<Result<Vec<_>>>()?;
-
-    let count1 = range_pairs
-        .iter()
-        .filter(|(range1, range2)| {
-            (range1.contains(range2.start()) && range1.contains(range2.end()))
-                || (range2.contains(range1.start()) && range2.contains(range1.end()))
-        })
-        .count();
-    println!("Part 1: {count1}");
-
-    let count2 = range_pairs
-        .iter()
-        .filter(|(range1, range2)| {
-            (range2.contains(range1.start())) || (range1.contains(range2.start()))
-        })
-        .count();
-    println!("Part 2: {count2}");
-
-    Ok(())
+use eyre::Result;
+
+use crate::{
+    parse::{Line, Lines, ParseError, Scanner},
+    solution::{Answer, Solution},
+};
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+
+    type Input = Vec<RangePair>;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        Ok(Lines::new(Self::DAY, data)
+            .map(|line| parse_ranges(&line))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part1(range_pairs: &Self::Input) -> Result<Answer> {
+        let count = range_pairs
+            .iter()
+            .filter(|(range1, range2)| {
+                (range1.contains(range2.start()) && range1.contains(range2.end()))
+                    || (range2.contains(range1.start()) && range2.contains(range1.end()))
+            })
+            .count();
+        count.try_into()
+    }
+
+    fn part2(range_pairs: &Self::Input) -> Result<Answer> {
+        let count = range_pairs
+            .iter()
+            .filter(|(range1, range2)| {
+                (range2.contains(range1.start())) || (range1.contains(range2.start()))
+            })
+            .count();
+        count.try_into()
+    }
 }
 
-fn parse_ranges(s: &str) -> Result<(RangeInclusive<u64>, RangeInclusive<u64>)> {
-    let (r1, r2) = s.split_once(',').context("Bad input")?;
-    let range1 = as_range(r1)?;
-    let range2 = as_range(r2)?;
+pub type RangePair = (RangeInclusive<u64>, RangeInclusive<u64>);
+
+pub fn parse_ranges(line: &Line) -> Result<RangePair, ParseError> {
+    let mut s = line.scanner();
+    let range1 = parse_range(&mut s)?;
+    s.tag(",")?;
+    let range2 = parse_range(&mut s)?;
+    s.end()?;
 
     Ok((range1, range2))
 }
 
-fn as_range(s: &str) -> Result<RangeInclusive<u64>> {
-    let (begin, end) = s.split_once('-').context("Bad input")?;
-    let low = begin.parse::<u64>()?;
-    let high = end.parse::<u64>()?;
+fn parse_range(s: &mut Scanner) -> Result<RangeInclusive<u64>, ParseError> {
+    let low = s.number::<u64>("a section number")?;
+    s.tag("-")?;
+    let high = s.number::<u64>("a section number")?;
 
     Ok(RangeInclusive::new(low, high))
 }
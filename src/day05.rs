@@ -1,45 +1,112 @@
 use std::{collections::VecDeque, fmt::Display};
 
-use eyre::{ContextCompat, Result};
-use once_cell::sync::Lazy;
-use regex::Regex;
+use eyre::{bail, ContextCompat, Result};
+use itertools::Itertools;
 
+use crate::{
+    parse::{Line, Lines, ParseError, Scanner},
+    solution::{Answer, Solution},
+};
+
+/// The stacks of crates, from bottom to top
 #[derive(Debug, Clone, Default)]
-struct Stacks([VecDeque<u8>; 9]);
+pub struct Stacks(Vec<VecDeque<u8>>);
 
 impl Stacks {
+    /// Create `num` empty stacks
+    pub fn new(num: usize) -> Self {
+        Self(vec![VecDeque::new(); num])
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The crates of the given stack (numbered from 1), from bottom to top
+    pub fn stack(&self, num: usize) -> Option<&VecDeque<u8>> {
+        self.0.get(num.checked_sub(1)?)
+    }
+
+    /// The stacks drawn as in the puzzle input: the rows of crates from the top one down, then
+    /// the stack numbers
+    pub fn drawing(&self) -> Vec<String> {
+        let height = self.0.iter().map(VecDeque::len).max().unwrap_or(0);
+        let mut lines = (0..height)
+            .rev()
+            .map(|row| {
+                self.0
+                    .iter()
+                    .map(|stack| match stack.get(row) {
+                        Some(c) => format!("[{}]", *c as char),
+                        None => "   ".to_string(),
+                    })
+                    .join(" ")
+            })
+            .collect_vec();
+        lines.push((1..=self.len()).map(|i| format!(" {i} ")).join(" "));
+        lines
+    }
+
     pub fn top_crates(&self) -> String {
-        let mut res = String::new();
-        (0..9).for_each(|i| {
-            res.push(self.0[i].back().cloned().unwrap_or(b' ').into());
-        });
-        res
+        self.0
+            .iter()
+            .map(|stack| stack.back().cloned().unwrap_or(b' ') as char)
+            .collect()
+    }
+
+    /// Move crates one at a time (CrateMover 9000)
+    pub fn apply_move1(&mut self, m: &Move) -> Result<()> {
+        let crates = self.take_crates(m)?;
+        self.stack_mut(m.2)?.extend(crates.into_iter().rev());
+        Ok(())
+    }
+
+    /// Move crates all at once (CrateMover 9001)
+    pub fn apply_move2(&mut self, m: &Move) -> Result<()> {
+        let crates = self.take_crates(m)?;
+        self.stack_mut(m.2)?.extend(crates);
+        Ok(())
     }
 
-    pub fn apply_move1(&mut self, &Move(num, from, to): &Move) {
-        for _ in 0..num {
-            let c = self.0[from - 1].pop_back().expect("Invalid input");
-            self.0[to - 1].push_back(c);
+    /// Remove the crates to move from the top of the source stack, from bottom to top
+    fn take_crates(&mut self, &Move(num, from, to): &Move) -> Result<Vec<u8>> {
+        // Check the destination first so that nothing is lost if it doesn't exist
+        self.stack_mut(to)?;
+        let stack = self.stack_mut(from)?;
+        if stack.len() < num {
+            bail!(
+                "Can't move {num} crates from stack {from}, which only has {}",
+                stack.len()
+            );
         }
+        let bounds_from = stack.len() - num;
+        Ok(stack.drain(bounds_from..).collect())
     }
 
-    pub fn apply_move2(&mut self, &Move(num, from, to): &Move) {
-        let len = self.0[from - 1].len();
-        let bounds_from = len - num;
-        // Clippy suggests removing collect(), but actually can't here as it would require 2
-        // mutable references to `self.0` at the same time. `rustc` doesn't know that `from` !=
-        // `to` :(
-        #[allow(clippy::needless_collect)]
-        let range = self.0[from - 1].drain(bounds_from..).collect::<Vec<_>>();
-        self.0[to - 1].extend(range.into_iter());
+    fn stack_mut(&mut self, num: usize) -> Result<&mut VecDeque<u8>> {
+        let len = self.len();
+        num.checked_sub(1)
+            .and_then(|i| self.0.get_mut(i))
+            .with_context(|| format!("No stack {num}, there are only {len}"))
+    }
+}
+
+/// The crates of each stack, from bottom to top
+impl From<Vec<Vec<u8>>> for Stacks {
+    fn from(stacks: Vec<Vec<u8>>) -> Self {
+        Self(stacks.into_iter().map(VecDeque::from).collect())
     }
 }
 
 impl Display for Stacks {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for i in 0..9 {
+        for (i, stack) in self.0.iter().enumerate() {
             write!(f, "Stack {}: ", i + 1)?;
-            for c in self.0[i].iter() {
+            for c in stack.iter() {
                 write!(f, "[{}]", *c as char)?;
             }
             writeln!(f)?;
@@ -48,58 +115,134 @@ impl Display for Stacks {
     }
 }
 
-pub fn run() -> Result<()> {
-    let data = std::fs::read_to_string("inputs/day05.txt")?;
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+
+    type Input = (Stacks, Vec<Move>);
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        Ok(parse_procedure(data)?)
+    }
+
+    fn part1((stacks, moves): &Self::Input) -> Result<Answer> {
+        let mut stacks = stacks.clone();
+        moves.iter().try_for_each(|m| stacks.apply_move1(m))?;
+        Ok(stacks.top_crates().into())
+    }
 
-    // Parse initial stacks configuration
-    let mut orig = Stacks::default();
-    for line in data.lines().take(8) {
-        let bytes = line.as_bytes();
-        (0..9).for_each(|stack| {
+    fn part2((stacks, moves): &Self::Input) -> Result<Answer> {
+        let mut stacks = stacks.clone();
+        moves.iter().try_for_each(|m| stacks.apply_move2(m))?;
+        Ok(stacks.top_crates().into())
+    }
+}
+
+/// Parse the starting stacks and the rearrangement procedure
+pub fn parse_procedure(data: &str) -> Result<(Stacks, Vec<Move>), ParseError> {
+    let mut lines = Lines::new(Day05::DAY, data);
+
+    // The drawing ends with an empty line
+    let mut drawing = Vec::new();
+    let separator = loop {
+        let line = lines.expect_line("an empty line after the drawing")?;
+        if line.text.trim().is_empty() {
+            break line;
+        }
+        drawing.push(line);
+    };
+    let Some((numbers, crates)) = drawing.split_last() else {
+        return Err(separator.error_at(0, "a drawing of the stacks"));
+    };
+
+    // Parse initial stacks configuration. The last line of the drawing has the stack numbers.
+    let mut s = numbers.scanner();
+    let mut num_stacks = 0;
+    loop {
+        s.take_while(|c| c == ' ');
+        if s.is_at_end() {
+            break;
+        }
+        let pos = s.pos();
+        if s.number::<usize>("a stack number")? != num_stacks + 1 {
+            return Err(numbers.error_at(pos, format!("stack number {}", num_stacks + 1)));
+        }
+        num_stacks += 1;
+    }
+
+    let mut stacks = Stacks::new(num_stacks);
+    for line in crates {
+        let bytes = line.text.as_bytes();
+        for stack in 0..num_stacks {
             let idx = stack * 4 + 1;
-            if bytes[idx] != b' ' {
-                orig.0[stack].push_front(bytes[idx]);
+            match bytes.get(idx) {
+                Some(b' ') | None => (),
+                Some(c) if c.is_ascii_uppercase() => stacks.0[stack].push_front(*c),
+                Some(_) => return Err(line.error_at(idx, "a crate (A-Z) or a space")),
             }
-        });
+        }
     }
 
     // Parse list of moves
-    let moves = data
-        .lines()
-        .skip(10)
-        .map(parse_move)
-        .collect::<Result<Vec<_>>>()?;
-
-    // Part 1
-    let mut stacks = orig.clone();
-    moves.iter().for_each(|m| stacks.apply_move1(m));
-    println!("Part 1: {}", stacks.top_crates());
-
-    // Part 2
-    let mut stacks = orig;
-    moves.iter().for_each(|m| stacks.apply_move2(m));
-    println!("Part 2: {}", stacks.top_crates());
-
-    Ok(())
+    let moves = lines
+        .map(|line| parse_move(&line, num_stacks))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((stacks, moves))
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Move(usize, usize, usize);
-
-static REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"move (\d+) from (\d+) to (\d+)"#).unwrap());
-fn parse_move(line: &str) -> Result<Move> {
-    let captures = REGEX.captures(line).context("Bad input")?;
-    let num = captures
-        .get(1)
-        .and_then(|n| n.as_str().parse::<usize>().ok())
-        .context("Bad input")?;
-    let from = captures
-        .get(2)
-        .and_then(|n| n.as_str().parse::<usize>().ok())
-        .context("Bad input")?;
-    let to = captures
-        .get(3)
-        .and_then(|n| n.as_str().parse::<usize>().ok())
-        .context("Bad input")?;
+/// Write the starting stacks and the rearrangement procedure as in the puzzle input
+pub fn render_procedure(stacks: &Stacks, moves: &[Move]) -> String {
+    let mut text = stacks.drawing().join("\n");
+    text += "\n\n";
+    for m in moves {
+        text += &format!("{m}\n");
+    }
+    text
+}
+
+/// Move a number of crates from a stack to another (both numbered from 1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move(pub usize, pub usize, pub usize);
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "move {} from {} to {}", self.0, self.1, self.2)
+    }
+}
+
+/// Parse a move between two of the `num_stacks` stacks
+pub fn parse_move(line: &Line, num_stacks: usize) -> Result<Move, ParseError> {
+    let mut s = line.scanner();
+    let stack = |s: &mut Scanner| {
+        let pos = s.pos();
+        let expected = format!("a stack number between 1 and {num_stacks}");
+        match s.number::<usize>(&expected)? {
+            n if (1..=num_stacks).contains(&n) => Ok(n),
+            _ => Err(line.error_at(pos, expected)),
+        }
+    };
+    s.tag("move ")?;
+    let num = s.number::<usize>("a number of crates")?;
+    s.tag(" from ")?;
+    let from = stack(&mut s)?;
+    s.tag(" to ")?;
+    let to = stack(&mut s)?;
+    s.end()?;
     Ok(Move(num, from, to))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_moves() {
+        let (mut stacks, _) = parse_procedure(include_str!("../examples/day05.txt")).unwrap();
+        assert!(stacks.apply_move1(&Move(3, 1, 2)).is_err());
+        assert!(stacks.apply_move2(&Move(1, 1, 4)).is_err());
+        assert!(stacks.apply_move2(&Move(1, 2, 1)).is_ok());
+        assert_eq!(stacks.top_crates(), "DCP");
+    }
+}
@@ -1,37 +1,64 @@
 use std::collections::HashSet;
 
-use eyre::Result;
+use eyre::{ContextCompat, Result};
 
-pub fn run() -> Result<()> {
-    let data = std::fs::read_to_string("inputs/day06.txt")?;
+use crate::{
+    parse::{Lines, ParseError},
+    solution::{Answer, Solution},
+};
 
-    let bytes = data.as_bytes();
-    let index = start_of_packet(bytes);
-    println!("Part 1: start-of-packet at index: {index}");
+pub struct Day06;
 
-    let index = start_of_message(bytes);
-    println!("Part 2: start-of-message at index: {index}");
+impl Solution for Day06 {
+    const DAY: u8 = 6;
 
-    Ok(())
+    type Input = Vec<u8>;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        Ok(parse_datastream(data)?)
+    }
+
+    fn part1(data: &Self::Input) -> Result<Answer> {
+        let index = start_of_packet(data).context("No start-of-packet marker")?;
+        index.try_into()
+    }
+
+    fn part2(data: &Self::Input) -> Result<Answer> {
+        let index = start_of_message(data).context("No start-of-message marker")?;
+        index.try_into()
+    }
+}
+
+/// Parse the datastream, a single line of lowercase letters
+pub fn parse_datastream(data: &str) -> Result<Vec<u8>, ParseError> {
+    let mut lines = Lines::new(Day06::DAY, data);
+    let line = lines.expect_line("a datastream")?;
+    let mut s = line.scanner();
+    s.take_while(|c| c.is_ascii_lowercase());
+    if !s.is_at_end() {
+        return Err(s.error("a lowercase letter"));
+    }
+    if let Some(line) = lines.next() {
+        return Err(line.error_at(0, "end of input"));
+    }
+    Ok(line.text.as_bytes().to_vec())
 }
 
-fn start_of_packet(data: &[u8]) -> usize {
+pub fn start_of_packet(data: &[u8]) -> Option<usize> {
     find_distinct_sequence(data, 4)
 }
 
-fn start_of_message(data: &[u8]) -> usize {
+pub fn start_of_message(data: &[u8]) -> Option<usize> {
     find_distinct_sequence(data, 14)
 }
 
-fn find_distinct_sequence(data: &[u8], len: usize) -> usize {
-    let index = data
-        .windows(len)
-        .position(|window| {
-            let set = window.iter().copied().collect::<HashSet<_>>();
-            set.len() == len
-        })
-        .unwrap();
-    index + len
+/// The number of characters read once the last `len` ones are all different
+fn find_distinct_sequence(data: &[u8], len: usize) -> Option<usize> {
+    let index = data.windows(len).position(|window| {
+        let set = window.iter().copied().collect::<HashSet<_>>();
+        set.len() == len
+    })?;
+    Some(index + len)
 }
 
 #[cfg(test)]
@@ -42,18 +69,18 @@ mod tests {
     fn test_start_of_packet() {
         let data = b"bvwbjplbgvbhsrlpgdmjqwftvncz";
         let index = start_of_packet(data);
-        assert_eq!(index, 5);
+        assert_eq!(index, Some(5));
 
         let data = b"nppdvjthqldpwncqszvftbrmjlhg";
         let index = start_of_packet(data);
-        assert_eq!(index, 6);
+        assert_eq!(index, Some(6));
 
         let data = b"nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
         let index = start_of_packet(data);
-        assert_eq!(index, 10);
+        assert_eq!(index, Some(10));
 
         let data = b"zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
         let index = start_of_packet(data);
-        assert_eq!(index, 11);
+        assert_eq!(index, Some(11));
     }
 }
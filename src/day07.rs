@@ -3,43 +3,62 @@ use std::{
     ops::{Deref, DerefMut},
 };
 
-use eyre::Result;
+use eyre::{bail, ContextCompat, Result};
 
-pub fn run() -> Result<()> {
-    let data = std::fs::read_to_string("inputs/day07.txt")?;
+use crate::{
+    parse::{Line, Lines, ParseError},
+    solution::{Answer, Solution},
+};
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+
+    type Input = Shell;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        let mut shell = Shell::new();
+        for line in Lines::new(Self::DAY, data) {
+            shell.parse_line(&line)?;
+        }
+        Ok(shell)
+    }
+
+    fn part1(shell: &Self::Input) -> Result<Answer> {
+        let total_size = shell
+            .compute_dir_sizes()?
+            .into_iter()
+            .map(|(_, s)| s)
+            .filter(|size| *size <= 100000)
+            .sum::<usize>();
 
-    let mut shell = Shell::new();
-    for line in data.lines() {
-        shell.parse_line(line);
+        total_size.try_into()
     }
 
-    let sizes = shell.compute_dir_sizes();
-    let total_size = sizes
-        .clone()
-        .into_iter()
-        .map(|(_, s)| s)
-        .filter(|size| *size <= 100000)
-        .sum::<usize>();
-
-    println!("Part 1: {total_size}");
-
-    const TOTAL_DISK_SPACE: usize = 70_000_000;
-    const FREE_SPACE_NEEDED: usize = 30_000_000;
-    let total_used_space = sizes
-        .iter()
-        .find_map(|(name, size)| if name == "/" { Some(size) } else { None })
-        .unwrap();
-    let total_unused_space = TOTAL_DISK_SPACE - total_used_space;
-    let space_to_free = FREE_SPACE_NEEDED - total_unused_space;
-    let size_of_dir_to_delete = sizes
-        .iter()
-        .filter_map(|(_name, size)| (*size >= space_to_free).then_some(*size))
-        .min()
-        .unwrap();
-
-    println!("Part 2: {size_of_dir_to_delete}");
-
-    Ok(())
+    fn part2(shell: &Self::Input) -> Result<Answer> {
+        const TOTAL_DISK_SPACE: usize = 70_000_000;
+        const FREE_SPACE_NEEDED: usize = 30_000_000;
+
+        let sizes = shell.compute_dir_sizes()?;
+        let total_used_space = sizes
+            .iter()
+            .find_map(|(name, size)| if name == "/" { Some(*size) } else { None })
+            .context("No root directory")?;
+        let total_unused_space = TOTAL_DISK_SPACE
+            .checked_sub(total_used_space)
+            .with_context(|| format!("{total_used_space} used on a disk of {TOTAL_DISK_SPACE}"))?;
+        let Some(space_to_free) = FREE_SPACE_NEEDED.checked_sub(total_unused_space) else {
+            bail!("There is already {total_unused_space} of free space");
+        };
+        let size_of_dir_to_delete = sizes
+            .iter()
+            .filter_map(|(_name, size)| (*size >= space_to_free).then_some(*size))
+            .min()
+            .context("No directory is big enough")?;
+
+        size_of_dir_to_delete.try_into()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
@@ -99,50 +118,59 @@ impl InodeTable {
     }
 
     /// Find a directory with the given name under the given parent name and return its inode
-    pub fn find_dir(&self, parent: Inode, dir_name: &str) -> Inode {
-        if let Some(Entry::Dir { entries, .. }) = self.table.get(&parent) {
-            entries
-                .iter()
-                .find(|i| {
-                    matches!(self.table.get(i), Some(Entry::Dir { name, .. }) if name.as_str() == dir_name)})
-                .copied()
-                .unwrap_or_else(|| {
-                    panic!("No directory with name {dir_name} found under parent {parent:?}")
-                })
-        } else {
-            panic!("Inode {parent:?} doesn't exist or is not a directory");
-        }
+    pub fn find_dir(&self, parent: Inode, dir_name: &str) -> Result<Inode> {
+        let Some(Entry::Dir { entries, .. }) = self.table.get(&parent) else {
+            bail!("Inode {parent:?} doesn't exist or is not a directory");
+        };
+        entries
+            .iter()
+            .find(
+                |i| matches!(self.table.get(i), Some(Entry::Dir { name, .. }) if name == dir_name),
+            )
+            .copied()
+            .with_context(|| {
+                format!("No directory with name {dir_name} found under parent {parent:?}")
+            })
     }
 
-    pub fn compute_dir_sizes(&self) -> Vec<(String, usize)> {
+    /// The name and total size of every directory, the root last
+    pub fn compute_dir_sizes(&self) -> Result<Vec<(String, usize)>> {
         let mut dirs = Vec::new();
 
-        let size = self.compute_dir_sizes_inner(Inode(0), &mut dirs);
+        let size = self.compute_dir_sizes_inner(Inode(0), &mut dirs)?;
         dirs.push(("/".to_string(), size));
-        dirs
+        Ok(dirs)
     }
 
-    fn compute_dir_sizes_inner(&self, root: Inode, acc: &mut Vec<(String, usize)>) -> usize {
+    fn compute_dir_sizes_inner(
+        &self,
+        root: Inode,
+        acc: &mut Vec<(String, usize)>,
+    ) -> Result<usize> {
         let mut total_size = 0usize;
         // root has to be a dir
-        if let Some(Entry::Dir { entries, .. }) = self.get(root) {
-            for e in entries {
-                match self.get(*e).unwrap() {
+        if let Some(Entry::Dir { name, entries, .. }) = self.get(root) {
+            for (e, entry) in entries.iter().filter_map(|e| Some((e, self.get(*e)?))) {
+                let size = match entry {
                     Entry::Dir { name, .. } => {
-                        let dir_size = self.compute_dir_sizes_inner(*e, acc);
+                        let dir_size = self.compute_dir_sizes_inner(*e, acc)?;
                         acc.push((name.clone(), dir_size));
-                        total_size += dir_size;
+                        dir_size
                     }
-                    Entry::File { size, .. } => total_size += size,
-                }
+                    Entry::File { size, .. } => *size,
+                };
+                total_size = total_size
+                    .checked_add(size)
+                    .with_context(|| format!("The size of directory {name} overflows"))?;
             }
         }
-        total_size
+        Ok(total_size)
     }
 }
 
+/// Replays a terminal session to reconstruct the file system it explored
 #[derive(Debug)]
-struct Shell {
+pub struct Shell {
     fs: InodeTable,
     cwd: Inode,
 }
@@ -154,24 +182,35 @@ impl Shell {
         Self { fs, cwd }
     }
 
-    pub fn parse_line(&mut self, line: &str) {
-        if let Some(new_dir) = line.strip_prefix("$ cd ") {
-            self.cd(new_dir);
-        } else if line.starts_with("$ ls") {
-            // nop
-        } else if let Some(dir_name) = line.strip_prefix("dir ") {
-            self.add_new_dir(dir_name);
+    /// The file system discovered so far
+    pub fn fs(&self) -> &InodeTable {
+        &self.fs
+    }
+
+    /// Replay a line of the terminal session: a command or an entry listed by `ls`
+    pub fn parse_line(&mut self, line: &Line) -> Result<(), ParseError> {
+        let mut s = line.scanner();
+        if s.try_tag("$ ") {
+            if s.try_tag("cd ") {
+                let pos = s.pos();
+                self.cd(s.take_rest())
+                    .map_err(|_| line.error_at(pos, "a directory listed by ls"))?;
+            } else {
+                s.tag("ls").map_err(|_| s.error("a command (cd or ls)"))?;
+                s.end()?;
+            }
+        } else if s.try_tag("dir ") {
+            self.add_new_dir(s.take_rest());
         } else {
             // must be a file entry
-            let (size_str, name) = line.split_once(' ').unwrap();
-            let size = size_str
-                .parse::<usize>()
-                .unwrap_or_else(|_| panic!("Invalid number: {size_str}"));
-            self.add_new_file(size, name);
+            let size = s.number::<usize>("a command, a directory or a file size")?;
+            s.tag(" ")?;
+            self.add_new_file(size, s.take_rest());
         }
+        Ok(())
     }
 
-    pub fn cd(&mut self, new_dir: &str) {
+    pub fn cd(&mut self, new_dir: &str) -> Result<()> {
         // println!("cd'ing into {new_dir}");
         if new_dir == "/" {
             self.cwd = Inode(0);
@@ -180,8 +219,9 @@ impl Shell {
                 self.cwd = *parent;
             }
         } else {
-            self.cwd = self.fs.find_dir(self.cwd, new_dir);
+            self.cwd = self.fs.find_dir(self.cwd, new_dir)?;
         }
+        Ok(())
     }
 
     pub fn add_new_dir(&mut self, dir_name: &str) {
@@ -193,7 +233,7 @@ impl Shell {
         };
         let inode = self.fs.add_entry(entry);
 
-        if let Entry::Dir { entries, .. } = self.fs.get_mut(self.cwd).unwrap() {
+        if let Some(Entry::Dir { entries, .. }) = self.fs.get_mut(self.cwd) {
             entries.push(inode);
         }
     }
@@ -206,16 +246,22 @@ impl Shell {
         };
         let inode = self.fs.add_entry(file_entry);
 
-        if let Entry::Dir { entries, .. } = self.fs.get_mut(self.cwd).unwrap() {
+        if let Some(Entry::Dir { entries, .. }) = self.fs.get_mut(self.cwd) {
             entries.push(inode);
         }
     }
 
-    pub fn compute_dir_sizes(&self) -> Vec<(String, usize)> {
+    pub fn compute_dir_sizes(&self) -> Result<Vec<(String, usize)>> {
         self.fs.compute_dir_sizes()
     }
 }
 
+impl Default for Shell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // #[derive(Debug)]
 // struct Fs {
 //     root: Rc<Entry>,
@@ -250,34 +296,29 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_name() {
-        let data = r"$ cd /
-$ ls
-dir a
-14848514 b.txt
-8504156 c.dat
-dir d
-$ cd a
-$ ls
-dir e
-29116 f
-2557 g
-62596 h.lst
-$ cd e
-$ ls
-584 i
-$ cd ..
-$ cd ..
-$ cd d
-$ ls
-4060174 j
-8033020 d.log
-5626152 d.ext
-7214296 k";
+    fn test_dir_sizes() {
+        let data = include_str!("../examples/day07.txt");
 
         let mut shell = Shell::new();
-        for line in data.lines() {
-            shell.parse_line(line);
+        for line in Lines::new(Day07::DAY, data) {
+            shell.parse_line(&line).unwrap();
         }
+        let sizes = shell.compute_dir_sizes().unwrap();
+        let size = |dir: &str| sizes.iter().find(|(name, _)| name == dir).unwrap().1;
+        assert_eq!(sizes.len(), 4);
+        assert_eq!(size("e"), 584);
+        assert_eq!(size("a"), 94853);
+        assert_eq!(size("d"), 24933642);
+        assert_eq!(size("/"), 48381165);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let data = format!("$ ls\n{} a\n1 b\n", usize::MAX);
+        let shell = Day07::parse(&data).unwrap();
+        let e = shell.compute_dir_sizes().unwrap_err();
+        assert_eq!(e.to_string(), "The size of directory / overflows");
+        assert!(Day07::part1(&shell).is_err());
+        assert!(Day07::part2(&shell).is_err());
     }
 }
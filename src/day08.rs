@@ -1,53 +1,63 @@
 use eyre::Result;
-use itertools::Itertools;
 
-pub fn run() -> Result<()> {
-    let data = std::fs::read_to_string("inputs/day08.txt")?;
-    let forest = Forest::<99, 99>::new(&data);
+use crate::{
+    geometry::UPoint,
+    grid::Grid,
+    parse::{Lines, ParseError},
+    solution::{Answer, Solution},
+};
 
-    let count = forest.num_trees_visible();
-    println!("Part 1: {count} trees are visible");
+pub struct Day08;
 
-    let best_score = forest.best_scenic_score();
-    println!("Part 2: {best_score} is the best scenic score");
+impl Solution for Day08 {
+    const DAY: u8 = 8;
 
-    Ok(())
+    type Input = Forest;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        Ok(Forest::new(data)?)
+    }
+
+    fn part1(forest: &Self::Input) -> Result<Answer> {
+        forest.num_trees_visible().try_into()
+    }
+
+    fn part2(forest: &Self::Input) -> Result<Answer> {
+        forest.best_scenic_score().try_into()
+    }
 }
 
-struct Forest<const W: usize, const H: usize> {
-    grid: Vec<u8>,
+/// A grid of tree heights
+pub struct Forest {
+    grid: Grid<u8>,
 }
 
-impl<const W: usize, const H: usize> Forest<W, H> {
-    pub fn new(data: &str) -> Self {
-        let grid = data
-            .lines()
-            .flat_map(|line| line.as_bytes().iter().copied())
-            .map(|c| c - b'0')
-            .collect::<Vec<u8>>();
-        assert_eq!(grid.len(), W * H);
-        Self { grid }
+impl Forest {
+    /// Parse lines of digits, all of the same length
+    pub fn new(data: &str) -> Result<Self, ParseError> {
+        let height = |c: char| c.to_digit(10).map(|d| d as u8);
+        let grid = Grid::parse(Lines::new(Day08::DAY, data), height, "tree height (0-9)")?;
+        Ok(Self { grid })
     }
 
-    pub fn num_trees_visible(&self) -> usize {
-        let mut count = 0;
-        for i in 0..W {
-            for j in 0..H {
-                if self.is_visible(i, j) {
-                    count += 1;
-                }
-            }
-        }
+    /// The heights of the trees
+    pub fn grid(&self) -> &Grid<u8> {
+        &self.grid
+    }
 
-        count
+    pub fn num_trees_visible(&self) -> usize {
+        self.grid
+            .positions()
+            .filter(|p| self.is_visible(p.x, p.y))
+            .count()
     }
 
     pub fn best_scenic_score(&self) -> usize {
-        (0..W - 1)
-            .cartesian_product(0..H - 1)
-            .map(|(i, j)| self.scenic_score(i, j))
+        self.grid
+            .positions()
+            .map(|p| self.scenic_score(p.x, p.y))
             .max()
-            .unwrap()
+            .unwrap_or(0)
     }
 
     pub fn scenic_score(&self, i: usize, j: usize) -> usize {
@@ -61,7 +71,7 @@ impl<const W: usize, const H: usize> Forest<W, H> {
             .look_bottom(i, j)
             .position(|t| t >= h)
             .map(|c| c + 1)
-            .unwrap_or(H - j - 1);
+            .unwrap_or(self.grid.height() - j - 1);
         let left = self
             .look_left(i, j)
             .position(|t| t >= h)
@@ -71,39 +81,34 @@ impl<const W: usize, const H: usize> Forest<W, H> {
             .look_right(i, j)
             .position(|t| t >= h)
             .map(|c| c + 1)
-            .unwrap_or(W - i - 1);
+            .unwrap_or(self.grid.width() - i - 1);
 
         top * bottom * left * right
     }
 
-    fn get(&self, i: usize, j: usize) -> u8 {
-        self.grid[j * W + i]
+    /// Height of the tree in column `i` and row `j`
+    pub fn get(&self, i: usize, j: usize) -> u8 {
+        self.grid[UPoint::new(i, j)]
     }
 
     fn look_top(&self, i: usize, j: usize) -> impl Iterator<Item = u8> + '_ {
-        self.grid.iter().skip(i).step_by(W).take(j).rev().copied()
+        self.grid.column(i).take(j).rev().copied()
     }
 
     fn look_bottom(&self, i: usize, j: usize) -> impl Iterator<Item = u8> + '_ {
-        self.grid.iter().skip(i).step_by(W).skip(j + 1).copied()
+        self.grid.column(i).skip(j + 1).copied()
     }
 
     fn look_left(&self, i: usize, j: usize) -> impl Iterator<Item = u8> + '_ {
-        self.grid.iter().skip(j * W).take(i).rev().copied()
+        self.grid.row(j)[..i].iter().rev().copied()
     }
 
     fn look_right(&self, i: usize, j: usize) -> impl Iterator<Item = u8> + '_ {
-        self.grid
-            .iter()
-            .skip(j * W)
-            .skip(i + 1)
-            .take(W - (i + 1))
-            .copied()
+        self.grid.row(j)[i + 1..].iter().copied()
     }
 
     fn is_visible_top(&self, i: usize, j: usize) -> bool {
         let h = self.get(i, j);
-        // self.grid.iter().skip(i).step_by(W).take(j).all(|t| *t < h)
         self.look_top(i, j).all(|t| t < h)
     }
 
@@ -122,7 +127,8 @@ impl<const W: usize, const H: usize> Forest<W, H> {
         self.look_right(i, j).all(|t| t < h)
     }
 
-    fn is_visible(&self, i: usize, j: usize) -> bool {
+    /// Whether the tree in column `i` and row `j` can be seen from outside the forest
+    pub fn is_visible(&self, i: usize, j: usize) -> bool {
         if self.is_edge(i, j) {
             true
         } else {
@@ -134,7 +140,7 @@ impl<const W: usize, const H: usize> Forest<W, H> {
     }
 
     fn is_edge(&self, i: usize, j: usize) -> bool {
-        i == 0 || j == 0 || i == W - 1 || j == H - 1
+        i == 0 || j == 0 || i == self.grid.width() - 1 || j == self.grid.height() - 1
     }
 }
 
@@ -144,12 +150,8 @@ mod tests {
 
     #[test]
     fn test() {
-        let data = "30373
-25512
-65332
-33549
-35390";
-        let forest = Forest::<5, 5>::new(data);
+        let data = include_str!("../examples/day08.txt");
+        let forest = Forest::new(data).unwrap();
         // edge (row 0)
         assert!(forest.is_visible(0, 0));
         // row 1
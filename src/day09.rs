@@ -1,59 +1,50 @@
-use std::{collections::HashSet, ops::Sub, str::FromStr};
+use std::{collections::HashSet, str::FromStr};
 
-use eyre::{eyre, Report, Result};
+use eyre::{Report, Result};
 
-pub fn run() -> Result<()> {
-    let data = std::fs::read_to_string("inputs/day09.txt")?;
-    let moves = data.parse::<Moves>()?;
+use crate::{
+    geometry::{Dir, IPoint},
+    parse::{Line, Lines, ParseError},
+    solution::{Answer, Solution},
+};
 
-    let mut grid = Grid::<1>::new();
-    grid.apply(&moves);
-    let distinct_positions = grid.tail_positions.len();
-    println!("Part 1: {distinct_positions} distinct tail positions with 2 knots");
+pub struct Day09;
 
-    let mut grid = Grid::<9>::new();
-    grid.apply(&moves);
-    let distinct_positions = grid.tail_positions.len();
-    println!("Part 2: {distinct_positions} distinct tail positions with 10 knots");
+impl Solution for Day09 {
+    const DAY: u8 = 9;
 
-    Ok(())
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
-pub struct Pos(isize, isize);
+    type Input = Moves;
 
-impl Sub<Pos> for Pos {
-    type Output = Pos;
+    fn parse(data: &str) -> Result<Self::Input> {
+        data.parse::<Moves>()
+    }
 
-    fn sub(self, rhs: Pos) -> Self::Output {
-        Pos(self.0 - rhs.0, self.1 - rhs.1)
+    fn part1(moves: &Self::Input) -> Result<Answer> {
+        let mut grid = Grid::<1>::new();
+        grid.apply(moves);
+        grid.tail_positions.len().try_into()
     }
-}
 
-impl Pos {
-    pub fn move_dir(&mut self, dir: Dir) {
-        match dir {
-            Dir::Up => self.1 += 1,
-            Dir::Down => self.1 -= 1,
-            Dir::Left => self.0 -= 1,
-            Dir::Right => self.0 += 1,
-        }
+    fn part2(moves: &Self::Input) -> Result<Answer> {
+        let mut grid = Grid::<9>::new();
+        grid.apply(moves);
+        grid.tail_positions.len().try_into()
     }
 }
 
+/// A rope made of a head followed by `N` knots
 #[derive(Debug)]
 pub struct Grid<const N: usize> {
-    head: Pos,
-    // tail: Pos,
-    tails: [Pos; N],
-    tail_positions: HashSet<Pos>,
+    head: IPoint,
+    tails: [IPoint; N],
+    tail_positions: HashSet<IPoint>,
 }
 
 impl<const N: usize> Grid<N> {
     pub fn new() -> Self {
         let mut grid = Self {
-            head: Pos::default(),
-            tails: [Pos::default(); N],
+            head: IPoint::default(),
+            tails: [IPoint::default(); N],
             tail_positions: HashSet::default(),
         };
 
@@ -62,6 +53,20 @@ impl<const N: usize> Grid<N> {
         grid
     }
 
+    pub fn head(&self) -> IPoint {
+        self.head
+    }
+
+    /// The positions of the knots following the head, the last one being the tail
+    pub fn tails(&self) -> &[IPoint; N] {
+        &self.tails
+    }
+
+    /// All the distinct positions the tail has visited so far
+    pub fn tail_positions(&self) -> &HashSet<IPoint> {
+        &self.tail_positions
+    }
+
     pub fn apply(&mut self, moves: &Moves) {
         moves.0.iter().for_each(|m| self.apply_move(*m));
     }
@@ -72,7 +77,7 @@ impl<const N: usize> Grid<N> {
 
     /// Move the head 1 unit into the given direction
     pub fn move_head(&mut self, dir: Dir) {
-        self.head.move_dir(dir);
+        self.head = self.head.step(dir);
 
         // Move tail accordingly
         self.move_tail(self.head, 0);
@@ -84,80 +89,59 @@ impl<const N: usize> Grid<N> {
         self.record_tail_pos();
     }
 
-    fn move_tail(&mut self, head_pos: Pos, tail_num: usize) {
+    fn move_tail(&mut self, head_pos: IPoint, tail_num: usize) {
         let tail = &mut self.tails[tail_num];
-        let Pos(dx, dy) = head_pos - *tail;
-        match (dx, dy) {
-            // Touching
-            (0, 0) => (),
-            (x, y) if x.abs() <= 1 && y.abs() <= 1 => (),
-            // 2 units directly up/down/left/right
-            (0, 2) => tail.move_dir(Dir::Up),
-            (0, -2) => tail.move_dir(Dir::Down),
-            (2, 0) => tail.move_dir(Dir::Right),
-            (-2, 0) => tail.move_dir(Dir::Left),
-            // Other cases
-            (dx, dy) => {
-                let horizontal = if dx > 0 { Dir::Right } else { Dir::Left };
-                let vertical = if dy > 0 { Dir::Up } else { Dir::Down };
-                tail.move_dir(horizontal);
-                tail.move_dir(vertical);
-            }
+        // Unless touching, move 1 unit towards the head, diagonally if not in the same row or
+        // column
+        if head_pos.chebyshev(*tail) > 1 {
+            *tail += (head_pos - *tail).signum();
         }
     }
 
     fn record_tail_pos(&mut self) {
-        self.tail_positions.insert(*self.tails.last().unwrap());
+        // Without knots, the head is its own tail
+        let tail = self.tails.last().copied().unwrap_or(self.head);
+        self.tail_positions.insert(tail);
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Dir {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl FromStr for Dir {
-    type Err = Report;
-
-    fn from_str(value: &str) -> Result<Self, Self::Err> {
-        match value {
-            "U" => Ok(Self::Up),
-            "D" => Ok(Self::Down),
-            "L" => Ok(Self::Left),
-            "R" => Ok(Self::Right),
-            _ => Err(eyre!("Invalid direction")),
-        }
+impl<const N: usize> Default for Grid<N> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct Move(Dir, usize);
-
-impl FromStr for Move {
-    type Err = Report;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (dir, num) = s.split_once(' ').ok_or_else(|| eyre!("Invalid move"))?;
-        let dir = dir.parse::<Dir>()?;
-        let num = num.parse::<usize>()?;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move(pub Dir, pub usize);
+
+impl Move {
+    pub fn parse(line: &Line) -> Result<Self, ParseError> {
+        let mut s = line.scanner();
+        let start = s.pos();
+        let dir = match s.take_while(|c| c.is_ascii_alphabetic()) {
+            "U" => Dir::Up,
+            "D" => Dir::Down,
+            "L" => Dir::Left,
+            "R" => Dir::Right,
+            _ => return Err(line.error_at(start, "a direction (U, D, L or R)")),
+        };
+        s.tag(" ")?;
+        let num = s.number::<usize>("a number of steps")?;
+        s.end()?;
 
         Ok(Self(dir, num))
     }
 }
 
-pub struct Moves(Vec<Move>);
+pub struct Moves(pub Vec<Move>);
 
 impl FromStr for Moves {
     type Err = Report;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let moves = s
-            .lines()
-            .map(|line| line.parse::<Move>())
-            .collect::<Result<Vec<Move>>>()?;
+        let moves = Lines::new(Day09::DAY, s)
+            .map(|line| Move::parse(&line))
+            .collect::<Result<Vec<Move>, _>>()?;
 
         Ok(Self(moves))
     }
@@ -169,16 +153,9 @@ mod tests {
 
     #[test]
     fn test_2_knots() {
-        let moves = "R 4
-U 4
-L 3
-D 1
-R 4
-D 1
-L 5
-R 2"
-        .parse::<Moves>()
-        .unwrap();
+        let moves = include_str!("../examples/day09.txt")
+            .parse::<Moves>()
+            .unwrap();
 
         let mut grid = Grid::<1>::new();
         grid.apply(&moves);
@@ -188,14 +165,7 @@ R 2"
 
     #[test]
     fn test_10_knots() {
-        let moves = "R 5
-U 8
-L 8
-D 3
-R 17
-D 10
-L 25
-U 20"
+        let moves = include_str!("../examples/day09-larger.txt")
             .parse::<Moves>()
             .unwrap();
         let mut grid = Grid::<9>::new();
@@ -1,25 +1,41 @@
-use std::{fmt::Display, str::FromStr};
+use std::fmt::Display;
 
-use eyre::{bail, ContextCompat, Report, Result};
+use eyre::{ContextCompat, Result};
 use itertools::Itertools;
 
-pub fn run() -> Result<()> {
-    let data = std::fs::read_to_string("inputs/day10.txt")?;
-    let program = data
-        .lines()
-        .map(|line| line.parse::<Inst>())
-        .collect::<Result<Vec<_>>>()?;
+use crate::{
+    parse::{Line, Lines, ParseError},
+    solution::{Answer, Solution},
+};
 
-    let mut cpu = Cpu::new();
-    cpu.run(&program);
+pub struct Day10;
 
-    println!("Part 1: {}", cpu.interesting_signals());
+impl Solution for Day10 {
+    const DAY: u8 = 10;
 
-    println!("Part 2:\n{}", cpu.crt);
-    Ok(())
+    type Input = Vec<Inst>;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        Ok(Lines::new(Self::DAY, data)
+            .map(|line| Inst::parse(&line))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part1(program: &Self::Input) -> Result<Answer> {
+        let mut cpu = Cpu::new();
+        cpu.run(program)?;
+        cpu.interesting_signals()?.try_into()
+    }
+
+    fn part2(program: &Self::Input) -> Result<Answer> {
+        let mut cpu = Cpu::new();
+        cpu.run(program)?;
+        Ok(cpu.crt.to_string().into())
+    }
 }
 
-struct Crt {
+/// The 40x6 screen drawn by the CPU
+pub struct Crt {
     pixels: [char; 40 * 6],
 }
 
@@ -34,13 +50,29 @@ impl Crt {
         let row = cycle / 40;
         let pos = cycle % 40;
 
-        if (sprite_pos - pos as isize).abs() <= 1 {
+        if sprite_pos.abs_diff(pos as isize) <= 1 {
             self.draw(pos, row);
         }
     }
 
+    /// Light the pixel in column `x` and row `y`, unless it is outside of the screen
     pub fn draw(&mut self, x: usize, y: usize) {
-        self.pixels[y * 40 + x] = '#';
+        if x < 40 {
+            if let Some(pixel) = self.pixels.get_mut(y * 40 + x) {
+                *pixel = '#';
+            }
+        }
+    }
+
+    /// Whether the pixel in column `x` and row `y` is lit
+    pub fn is_lit(&self, x: usize, y: usize) -> bool {
+        x < 40 && self.pixels.get(y * 40 + x) == Some(&'#')
+    }
+}
+
+impl Default for Crt {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -55,7 +87,7 @@ impl Display for Crt {
     }
 }
 
-struct Cpu {
+pub struct Cpu {
     x: isize,
     cycle: usize,
     signal: Vec<isize>,
@@ -72,66 +104,104 @@ impl Cpu {
         }
     }
 
-    pub fn run(&mut self, program: &[Inst]) {
-        program.iter().for_each(|i| self.step(i));
+    /// Value of the X register
+    pub fn x(&self) -> isize {
+        self.x
+    }
+
+    /// The signal strength during each cycle, starting with cycle 0
+    pub fn signal(&self) -> &[isize] {
+        &self.signal
+    }
+
+    pub fn crt(&self) -> &Crt {
+        &self.crt
     }
 
-    pub fn step(&mut self, inst: &Inst) {
-        let cycles = inst.cycles();
-        (0..cycles).for_each(|_| self.cycle());
+    /// Number of cycles run so far
+    pub fn cycles(&self) -> usize {
+        self.cycle
+    }
+
+    pub fn run(&mut self, program: &[Inst]) -> Result<()> {
+        program.iter().try_for_each(|i| self.step(i))
+    }
+
+    pub fn step(&mut self, inst: &Inst) -> Result<()> {
+        self.step_with(inst, |_| ())
+    }
+
+    /// Execute an instruction, calling `on_cycle` after each of the cycles it takes
+    pub fn step_with(&mut self, inst: &Inst, mut on_cycle: impl FnMut(&Self)) -> Result<()> {
+        for _ in 0..inst.cycles() {
+            self.cycle()?;
+            on_cycle(self);
+        }
 
         match inst {
-            Inst::AddX(v) => self.x += v,
+            Inst::AddX(v) => {
+                self.x = self
+                    .x
+                    .checked_add(*v)
+                    .with_context(|| format!("X overflows when adding {v} to {}", self.x))?
+            }
             Inst::Noop => (),
         }
+        Ok(())
     }
 
-    pub fn cycle(&mut self) {
+    pub fn cycle(&mut self) -> Result<()> {
         self.crt.tick(self.cycle, self.x);
         self.cycle += 1;
-        self.signal.push(self.cycle as isize * self.x);
+        let strength = (self.cycle as isize).checked_mul(self.x).with_context(|| {
+            format!("The signal strength overflows during cycle {}", self.cycle)
+        })?;
+        self.signal.push(strength);
+        Ok(())
     }
 
-    pub fn interesting_signals(&self) -> isize {
+    pub fn interesting_signals(&self) -> Result<isize> {
         self.signal
             .iter()
             .skip(20)
             .step_by(40)
             .take(6)
-            .sum::<isize>()
+            .try_fold(0isize, |acc, &s| acc.checked_add(s))
+            .context("The sum of the signal strengths overflows")
+    }
+}
+
+impl Default for Cpu {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Inst {
+pub enum Inst {
     AddX(isize),
     Noop,
 }
 
 impl Inst {
-    fn cycles(&self) -> usize {
+    pub fn cycles(&self) -> usize {
         match self {
             Inst::AddX(_) => 2,
             Inst::Noop => 1,
         }
     }
-}
 
-impl FromStr for Inst {
-    type Err = Report;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "noop" => Ok(Self::Noop),
-            _ => {
-                let (inst, v) = s.split_once(' ').context("Invalid instruction")?;
-                if inst != "addx" {
-                    bail!("Invalid instruction");
-                }
-                let v = v.parse::<isize>()?;
-                Ok(Self::AddX(v))
-            }
-        }
+    pub fn parse(line: &Line) -> Result<Self, ParseError> {
+        let mut s = line.scanner();
+        let inst = if s.try_tag("noop") {
+            Self::Noop
+        } else if s.try_tag("addx ") {
+            Self::AddX(s.number::<isize>("a number")?)
+        } else {
+            return Err(s.error("an instruction (noop or addx)"));
+        };
+        s.end()?;
+        Ok(inst)
     }
 }
 
@@ -141,160 +211,11 @@ mod tests {
 
     #[test]
     fn test() {
-        let data = "addx 15
-addx -11
-addx 6
-addx -3
-addx 5
-addx -1
-addx -8
-addx 13
-addx 4
-noop
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx 5
-addx -1
-addx -35
-addx 1
-addx 24
-addx -19
-addx 1
-addx 16
-addx -11
-noop
-noop
-addx 21
-addx -15
-noop
-noop
-addx -3
-addx 9
-addx 1
-addx -3
-addx 8
-addx 1
-addx 5
-noop
-noop
-noop
-noop
-noop
-addx -36
-noop
-addx 1
-addx 7
-noop
-noop
-noop
-addx 2
-addx 6
-noop
-noop
-noop
-noop
-noop
-addx 1
-noop
-noop
-addx 7
-addx 1
-noop
-addx -13
-addx 13
-addx 7
-noop
-addx 1
-addx -33
-noop
-noop
-noop
-addx 2
-noop
-noop
-noop
-addx 8
-noop
-addx -1
-addx 2
-addx 1
-noop
-addx 17
-addx -9
-addx 1
-addx 1
-addx -3
-addx 11
-noop
-noop
-addx 1
-noop
-addx 1
-noop
-noop
-addx -13
-addx -19
-addx 1
-addx 3
-addx 26
-addx -30
-addx 12
-addx -1
-addx 3
-addx 1
-noop
-noop
-noop
-addx -9
-addx 18
-addx 1
-addx 2
-noop
-noop
-addx 9
-noop
-noop
-noop
-addx -1
-addx 2
-addx -37
-addx 1
-addx 3
-noop
-addx 15
-addx -21
-addx 22
-addx -6
-addx 1
-noop
-addx 2
-addx 1
-noop
-addx -10
-noop
-noop
-addx 20
-addx 1
-addx 2
-addx 2
-addx -6
-addx -11
-noop
-noop
-noop";
-        let program = data
-            .lines()
-            .map(|line| line.parse::<Inst>())
-            .collect::<Result<Vec<_>>>()
-            .unwrap();
+        let data = include_str!("../examples/day10.txt");
+        let program = Day10::parse(data).unwrap();
 
         let mut cpu = Cpu::new();
-        cpu.run(&program);
+        cpu.run(&program).unwrap();
 
         assert_eq!(cpu.signal[20], 420);
         assert_eq!(cpu.signal[60], 1140);
@@ -303,7 +224,7 @@ noop";
         assert_eq!(cpu.signal[180], 2880);
         assert_eq!(cpu.signal[220], 3960);
 
-        assert_eq!(cpu.interesting_signals(), 13140);
+        assert_eq!(cpu.interesting_signals().unwrap(), 13140);
 
         let expected_crt = "##..##..##..##..##..##..##..##..##..##..
 ###...###...###...###...###...###...###.
@@ -316,4 +237,18 @@ noop";
 
         assert_eq!(crt, expected_crt);
     }
+
+    #[test]
+    fn test_overflow() {
+        let program = Day10::parse("addx 9223372036854775807\naddx 9223372036854775807\n").unwrap();
+        assert!(Day10::part1(&program).is_err());
+        assert!(Day10::part2(&program).is_err());
+
+        // X stays in range but the signal strength doesn't
+        let program = Day10::parse("addx 9223372036854775806\nnoop\n").unwrap();
+        assert!(Day10::part2(&program).is_err());
+
+        let program = Day10::parse("addx -9223372036854775807\nnoop\n").unwrap();
+        assert!(Cpu::new().run(&program).is_err());
+    }
 }
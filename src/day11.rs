@@ -1,55 +1,84 @@
-use std::{cell::RefCell, collections::VecDeque, str::FromStr};
+use std::{collections::VecDeque, fmt::Display, str::FromStr};
 
-use eyre::{bail, Context, ContextCompat, Report, Result};
-use once_cell::sync::OnceCell;
-use regex::Regex;
+use eyre::{ContextCompat, Report, Result};
+use itertools::Itertools;
 
-pub fn run() -> Result<()> {
-    let data = std::fs::read_to_string("inputs/day11.txt")?;
+use crate::{
+    parse::{Line, Lines, ParseError},
+    solution::{Answer, Solution},
+};
 
-    {
-        let mut monkeys = data.parse::<Monkeys>()?;
-        (0..20).for_each(|_| monkeys.round(true));
-        let monkey_business_level = monkeys.monkey_business_level();
-        println!("Part 1: monkey_business_level = {monkey_business_level}");
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = Monkeys;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        data.parse::<Monkeys>()
     }
 
-    {
-        let mut monkeys = data.parse::<Monkeys>()?;
-        (0..10000).for_each(|_| monkeys.round(false));
-        let monkey_business_level = monkeys.monkey_business_level();
-        println!("Part 2: monkey_business_level = {monkey_business_level}");
+    fn part1(monkeys: &Self::Input) -> Result<Answer> {
+        let mut monkeys = monkeys.clone();
+        (0..20).try_for_each(|_| monkeys.round(true))?;
+        monkeys.monkey_business_level()?.try_into()
     }
 
-    Ok(())
+    fn part2(monkeys: &Self::Input) -> Result<Answer> {
+        let mut monkeys = monkeys.clone();
+        (0..10000).try_for_each(|_| monkeys.round(false))?;
+        monkeys.monkey_business_level()?.try_into()
+    }
 }
 
-pub struct Monkeys(Vec<Monkey>);
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Monkeys(pub Vec<Monkey>);
 
 impl FromStr for Monkeys {
     type Err = Report;
 
     fn from_str(data: &str) -> Result<Self, Self::Err> {
-        let lines = data.lines().collect::<Vec<_>>();
-
-        let monkeys = lines
-            .chunks(7)
-            .map(Monkey::parse)
-            .collect::<Result<Vec<_>>>()?;
-        let monkeys = Monkeys(monkeys);
-        Ok(monkeys)
+        let mut lines = Lines::new(Day11::DAY, data);
+
+        // Monkeys are separated by an empty line
+        let mut throws = Vec::new();
+        let mut monkeys = vec![Monkey::parse(&mut lines, 0, &mut throws)?];
+        while let Some(line) = lines.next() {
+            if !line.text.is_empty() {
+                return Err(line.error_at(0, "an empty line between monkeys").into());
+            }
+            // The last monkey may be followed by an empty line too
+            if lines.clone().next().is_none() {
+                break;
+            }
+            monkeys.push(Monkey::parse(&mut lines, monkeys.len(), &mut throws)?);
+        }
+
+        if let Some(throw) = throws.iter().find(|t| t.target >= monkeys.len()) {
+            let expected = format!("a monkey number below {}", monkeys.len());
+            return Err(throw.line.error_at(throw.pos, expected).into());
+        }
+        Ok(Monkeys(monkeys))
     }
 }
 
-static FACTORS: OnceCell<usize> = OnceCell::new();
 impl Monkeys {
-    pub fn round(&mut self, with_relief: bool) {
-        let factors =
-            FACTORS.get_or_init(|| self.0.iter().map(|m| m.div_test.0).product::<usize>());
-
-        for monkey in &self.0 {
-            while let Some(Item(worry_level)) = &monkey.pop_item() {
-                let mut new = monkey.operation.eval(*worry_level);
+    pub fn round(&mut self, with_relief: bool) -> Result<()> {
+        let factors = self
+            .0
+            .iter()
+            .try_fold(1usize, |acc, m| acc.checked_mul(m.div_test.0))
+            .context("The product of the divisors overflows")?;
+
+        for i in 0..self.0.len() {
+            while let Some(Item(worry_level)) = self.0[i].pop_item() {
+                let monkey = &self.0[i];
+                let mut new = monkey.operation.eval(worry_level).with_context(|| {
+                    format!(
+                        "Worry level overflow when monkey {i} inspects an item of {worry_level}"
+                    )
+                })?;
                 if with_relief {
                     new /= 3;
                 } else {
@@ -59,147 +88,173 @@ impl Monkeys {
                 self.0[throw_to].send_item(Item(new));
             }
         }
+        Ok(())
     }
 
-    pub fn monkey_business_level(&mut self) -> usize {
-        let mut inspections = self
-            .0
-            .iter()
-            .map(|m| *m.num_inspections.borrow())
-            .collect::<Vec<_>>();
+    pub fn monkey_business_level(&mut self) -> Result<usize> {
+        let mut inspections = self.0.iter().map(|m| m.num_inspections).collect::<Vec<_>>();
         inspections.sort();
         inspections.reverse();
-        inspections.into_iter().take(2).product::<usize>()
+        inspections
+            .into_iter()
+            .take(2)
+            .try_fold(1usize, |acc, n| acc.checked_mul(n))
+            .context("The monkey business level overflows")
     }
 }
 
-fn parse_monkey_id(line: &str) -> Result<usize> {
-    let id = line
-        .strip_prefix("Monkey ")
-        .unwrap()
-        .strip_suffix(':')
-        .unwrap()
-        .parse::<usize>()?;
-    Ok(id)
-}
-
-fn parse_items(line: &str) -> Result<VecDeque<Item>> {
-    let numbers = line
-        .strip_prefix("  Starting items: ")
-        .unwrap()
-        .split(", ")
-        .map(|n| {
-            n.parse::<usize>()
-                .map(Item)
-                .context("Failed to parse number")
-        })
-        .collect::<Result<VecDeque<Item>>>()?;
-    Ok(numbers)
-}
-
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Monkey {
     pub id: usize,
-    pub starting_items: RefCell<VecDeque<Item>>,
+    pub starting_items: VecDeque<Item>,
     pub operation: Operation,
     pub div_test: DivisibilityTest,
     pub throw_to_if_true: usize,
     pub throw_to_if_false: usize,
-    pub num_inspections: RefCell<usize>,
+    pub num_inspections: usize,
 }
 
 impl Monkey {
-    pub fn parse(lines: &[&str]) -> Result<Self> {
-        let id = parse_monkey_id(lines[0])?;
-        let items = parse_items(lines[1])?;
-        let operation = lines[2].parse::<Operation>()?;
-        let div_test = lines[3].parse::<DivisibilityTest>()?;
-        let if_true = lines[4]
-            .strip_prefix("    If true: throw to monkey ")
-            .context("Invalid if true")?
-            .parse::<usize>()?;
-        let if_false = lines[5]
-            .strip_prefix("    If false: throw to monkey ")
-            .context("Invalid if false")?
-            .parse::<usize>()?;
-
-        Ok(Self {
+    /// Parse the 6 lines describing the monkey numbered `id`. Where it throws to is added to
+    /// `throws`, to be checked once the number of monkeys is known.
+    fn parse<'a>(
+        lines: &mut Lines<'a>,
+        id: usize,
+        throws: &mut Vec<Throw<'a>>,
+    ) -> Result<Self, ParseError> {
+        let line = lines.expect_line("a monkey")?;
+        let mut s = line.scanner();
+        s.tag("Monkey ")?;
+        let pos = s.pos();
+        if s.number::<usize>("a monkey number")? != id {
+            return Err(line.error_at(pos, format!("monkey number {id}")));
+        }
+        s.tag(":")?;
+        s.end()?;
+
+        let line = lines.expect_line("the starting items")?;
+        let mut s = line.scanner();
+        s.tag("  Starting items: ")?;
+        let mut items = VecDeque::new();
+        while !s.is_at_end() {
+            if !items.is_empty() {
+                s.tag(", ")?;
+            }
+            items.push_back(Item(s.number::<usize>("a worry level")?));
+        }
+
+        let operation = Operation::parse(&lines.expect_line("an operation")?)?;
+        let div_test = DivisibilityTest::parse(&lines.expect_line("a test")?)?;
+        let if_true = parse_throw(lines.expect_line("a monkey to throw to")?, "true", id)?;
+        let if_false = parse_throw(lines.expect_line("a monkey to throw to")?, "false", id)?;
+
+        let monkey = Self {
             id,
-            starting_items: RefCell::new(items),
+            starting_items: items,
             operation,
             div_test,
-            throw_to_if_true: if_true,
-            throw_to_if_false: if_false,
-            num_inspections: RefCell::new(0),
-        })
+            throw_to_if_true: if_true.target,
+            throw_to_if_false: if_false.target,
+            num_inspections: 0,
+        };
+        throws.extend([if_true, if_false]);
+        Ok(monkey)
     }
 
     pub fn check_worry_level(&self, worry_level: usize) -> usize {
-        if worry_level % self.div_test.0 == 0 {
+        if worry_level.is_multiple_of(self.div_test.0) {
             self.throw_to_if_true
         } else {
             self.throw_to_if_false
         }
     }
 
-    pub fn pop_item(&self) -> Option<Item> {
-        let mut items = self.starting_items.borrow_mut();
-        let item = items.pop_front();
+    pub fn pop_item(&mut self) -> Option<Item> {
+        let item = self.starting_items.pop_front();
         if item.is_some() {
-            self.num_inspections.replace_with(|v| *v + 1);
+            self.num_inspections += 1;
         }
         item
     }
 
-    pub fn send_item(&self, item: Item) {
-        let mut items = self.starting_items.borrow_mut();
+    pub fn send_item(&mut self, item: Item) {
+        self.starting_items.push_back(item);
+    }
+}
+
+/// The 6 lines describing the monkey in the puzzle input
+impl Display for Monkey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (op, lhs, rhs) = match &self.operation {
+            Operation::Add(lhs, rhs) => ('+', lhs, rhs),
+            Operation::Mult(lhs, rhs) => ('*', lhs, rhs),
+        };
+        writeln!(f, "Monkey {}:", self.id)?;
+        let items = self.starting_items.iter().map(|item| item.0);
+        writeln!(f, "  Starting items: {}", items.format(", "))?;
+        writeln!(f, "  Operation: new = {lhs} {op} {rhs}")?;
+        writeln!(f, "  Test: divisible by {}", self.div_test.0)?;
+        writeln!(f, "    If true: throw to monkey {}", self.throw_to_if_true)?;
+        writeln!(
+            f,
+            "    If false: throw to monkey {}",
+            self.throw_to_if_false
+        )
+    }
+}
+
+/// The monkey another one throws to, and where it was read
+struct Throw<'a> {
+    target: usize,
+    line: Line<'a>,
+    pos: usize,
+}
 
-        items.push_back(item);
+/// Parse where the monkey numbered `id` throws when its test is `condition`, which can't be
+/// itself
+fn parse_throw<'a>(line: Line<'a>, condition: &str, id: usize) -> Result<Throw<'a>, ParseError> {
+    let mut s = line.scanner();
+    s.tag(&format!("    If {condition}: throw to monkey "))?;
+    let pos = s.pos();
+    let target = s.number::<usize>("a monkey number")?;
+    if target == id {
+        return Err(line.error_at(pos, format!("a monkey other than {id}")));
     }
+    s.end()?;
+    Ok(Throw { target, line, pos })
 }
 
-pub struct Item(usize);
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item(pub usize);
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Operation {
     Add(Val, Val),
     Mult(Val, Val),
 }
 
 impl Operation {
-    pub fn eval(&self, old: usize) -> usize {
+    /// The new worry level, or `None` if it overflows
+    pub fn eval(&self, old: usize) -> Option<usize> {
         match self {
-            Operation::Add(lhs, rhs) => lhs.eval(old) + rhs.eval(old),
-            Operation::Mult(lhs, rhs) => lhs.eval(old) * rhs.eval(old),
+            Operation::Add(lhs, rhs) => lhs.eval(old).checked_add(rhs.eval(old)),
+            Operation::Mult(lhs, rhs) => lhs.eval(old).checked_mul(rhs.eval(old)),
         }
     }
-}
 
-static REGEX: OnceCell<Regex> = OnceCell::new();
-impl FromStr for Operation {
-    type Err = Report;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let line = s
-            .strip_prefix("  Operation: new = ")
-            .context("Invalid operation")?;
-
-        let re = REGEX
-            .get_or_try_init(|| Regex::new(r"(.*) (\+|\*) (.*)"))
-            .unwrap();
-        let matches = re.captures(line).unwrap();
-        let lhs = matches
-            .get(1)
-            .context("invalid op")
-            .and_then(|v| v.as_str().parse::<Val>())?;
-        let op = matches.get(2).context("invalid op")?.as_str();
-        let rhs = matches
-            .get(3)
-            .context("invalid op")
-            .and_then(|v| v.as_str().parse::<Val>())?;
+    pub fn parse(line: &Line) -> Result<Self, ParseError> {
+        let mut s = line.scanner();
+        s.tag("  Operation: new = ")?;
+        let lhs = s.parse::<Val>(|c| c.is_ascii_alphanumeric(), "old or a number")?;
+        s.tag(" ")?;
+        let op = s.one_of("+*")?;
+        s.tag(" ")?;
+        let rhs = s.parse::<Val>(|c| c.is_ascii_alphanumeric(), "old or a number")?;
+        s.end()?;
 
         let op = match op {
-            "+" => Self::Add(lhs, rhs),
-            "*" => Self::Mult(lhs, rhs),
-            _ => bail!("Invalid operator"),
+            '+' => Self::Add(lhs, rhs),
+            _ => Self::Mult(lhs, rhs),
         };
         Ok(op)
     }
@@ -220,6 +275,15 @@ impl Val {
     }
 }
 
+impl Display for Val {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Val::Old => write!(f, "old"),
+            Val::Literal(v) => write!(f, "{v}"),
+        }
+    }
+}
+
 impl FromStr for Val {
     type Err = Report;
 
@@ -233,16 +297,19 @@ impl FromStr for Val {
     }
 }
 
-pub struct DivisibilityTest(usize);
-
-impl FromStr for DivisibilityTest {
-    type Err = Report;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let div_by = s
-            .strip_prefix("  Test: divisible by ")
-            .context("Invalid divisibility test")?
-            .parse::<usize>()?;
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DivisibilityTest(pub usize);
+
+impl DivisibilityTest {
+    pub fn parse(line: &Line) -> Result<Self, ParseError> {
+        let mut s = line.scanner();
+        s.tag("  Test: divisible by ")?;
+        let pos = s.pos();
+        let div_by = s.number::<usize>("a positive number")?;
+        if div_by == 0 {
+            return Err(line.error_at(pos, "a positive number"));
+        }
+        s.end()?;
         Ok(Self(div_by))
     }
 }
@@ -253,41 +320,42 @@ mod tests {
 
     #[test]
     fn test() {
-        let data = "Monkey 0:
-  Starting items: 79, 98
-  Operation: new = old * 19
-  Test: divisible by 23
-    If true: throw to monkey 2
-    If false: throw to monkey 3
-
-Monkey 1:
-  Starting items: 54, 65, 75, 74
-  Operation: new = old + 6
-  Test: divisible by 19
-    If true: throw to monkey 2
-    If false: throw to monkey 0
-
-Monkey 2:
-  Starting items: 79, 60, 97
-  Operation: new = old * old
-  Test: divisible by 13
-    If true: throw to monkey 1
-    If false: throw to monkey 3
-
-Monkey 3:
-  Starting items: 74
-  Operation: new = old + 3
-  Test: divisible by 17
-    If true: throw to monkey 0
-    If false: throw to monkey 1";
+        let data = include_str!("../examples/day11.txt");
         let mut monkeys = data.parse::<Monkeys>().unwrap();
-        (0..20).for_each(|_| monkeys.round(true));
-        let monkey_business_level = monkeys.monkey_business_level();
+        (0..20).for_each(|_| monkeys.round(true).unwrap());
+        let monkey_business_level = monkeys.monkey_business_level().unwrap();
         assert_eq!(monkey_business_level, 10605);
 
         let mut monkeys = data.parse::<Monkeys>().unwrap();
-        (0..10000).for_each(|_| monkeys.round(false));
-        let monkey_business_level = monkeys.monkey_business_level();
+        (0..10000).for_each(|_| monkeys.round(false).unwrap());
+        let monkey_business_level = monkeys.monkey_business_level().unwrap();
         assert_eq!(monkey_business_level, 2713310158);
+
+        monkeys.0[0].num_inspections = usize::MAX;
+        assert!(monkeys.monkey_business_level().is_err());
+    }
+
+    #[test]
+    fn test_trailing_empty_line() {
+        let data = include_str!("../examples/day11.txt");
+        let monkeys = data.parse::<Monkeys>().unwrap();
+        assert_eq!(format!("{data}\n").parse::<Monkeys>().unwrap(), monkeys);
+        assert!(format!("{data}\n\n").parse::<Monkeys>().is_err());
+    }
+
+    #[test]
+    fn test_monkey_numbers() {
+        let data = include_str!("../examples/day11.txt");
+        let error = |data: &str| {
+            let e = data.parse::<Monkeys>().unwrap_err();
+            let e = e.downcast_ref::<ParseError>().unwrap();
+            (e.line, e.column, e.expected.clone())
+        };
+        let renumbered = data.replacen("Monkey 1:", "Monkey 2:", 1);
+        assert_eq!(error(&renumbered), (8, 8, "monkey number 1".into()));
+        let to_itself = data.replacen("throw to monkey 2", "throw to monkey 0", 1);
+        assert_eq!(error(&to_itself), (5, 30, "a monkey other than 0".into()));
+        let to_nobody = data.replacen("throw to monkey 3", "throw to monkey 4", 1);
+        assert_eq!(error(&to_nobody), (6, 31, "a monkey number below 4".into()));
     }
 }
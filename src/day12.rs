@@ -1,88 +1,91 @@
-use eyre::Result;
-
-pub fn run() -> Result<()> {
-    let data = std::fs::read_to_string("inputs/day12.txt")?;
-
-    let bytes = data
-        .lines()
-        .flat_map(|line| line.as_bytes().iter().copied())
-        .collect::<Vec<u8>>();
-    let grid = Grid::<161, 41>::new(bytes);
-    let (shortest_path, _cost) = grid.shortest_path().expect("Failed to find shortest path");
-    let num_steps = shortest_path.len() - 1;
-    println!("Part 1: shortest path has {num_steps} steps");
-
-    let shortest_path_steps = grid
-        .shortest_path_from_any_pos()
-        .map(|v| v.0.len())
-        .unwrap()
-        - 1;
-    println!("Part 2: shortest path from any lowest position has {shortest_path_steps}");
-
-    Ok(())
-}
+use eyre::{ContextCompat, Result};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct Pos(usize, usize);
+use crate::{
+    geometry::UPoint,
+    grid::Grid,
+    parse::Lines,
+    solution::{Answer, Solution},
+};
 
-impl Pos {
-    pub fn neighbours(&self) -> Vec<Pos> {
-        [self.up(), self.down(), self.right(), self.left()]
-            .iter()
-            .filter_map(|v| *v)
-            .collect()
-    }
+pub struct Day12;
 
-    pub fn up(&self) -> Option<Pos> {
-        // We have to use a closure as it could underflow if eagerly evaluated
-        #[allow(clippy::unnecessary_lazy_evaluations)]
-        (self.1 > 0).then(|| Pos(self.0, self.1 - 1))
-    }
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+
+    type Input = HeightMap;
 
-    pub fn down(&self) -> Option<Pos> {
-        Some(Pos(self.0, self.1 + 1))
+    fn parse(data: &str) -> Result<Self::Input> {
+        HeightMap::parse(data)
     }
 
-    pub fn left(&self) -> Option<Pos> {
-        // We have to use a closure as it could underflow if eagerly evaluated
-        #[allow(clippy::unnecessary_lazy_evaluations)]
-        (self.0 > 0).then(|| Pos(self.0 - 1, self.1))
+    fn part1(map: &Self::Input) -> Result<Answer> {
+        let (shortest_path, _cost) = map
+            .shortest_path()
+            .context("No path from the start to the goal")?;
+        let num_steps = shortest_path.len() - 1;
+        num_steps.try_into()
     }
 
-    pub fn right(&self) -> Option<Pos> {
-        Some(Pos(self.0 + 1, self.1))
+    fn part2(map: &Self::Input) -> Result<Answer> {
+        let shortest_path_steps = map
+            .shortest_path_from_any_pos()
+            .map(|v| v.0.len())
+            .context("No path from an elevation a to the goal")?
+            - 1;
+        shortest_path_steps.try_into()
     }
 }
 
-pub struct Grid<const W: usize, const H: usize> {
-    data: Vec<u8>,
-    start: Pos,
-    goal: Pos,
+/// A height map, along with the start and goal positions
+pub struct HeightMap {
+    grid: Grid<u8>,
+    start: UPoint,
+    goal: UPoint,
 }
 
-impl<const W: usize, const H: usize> Grid<W, H> {
-    pub fn new(data: Vec<u8>) -> Self {
-        let start_pos = data
-            .iter()
+impl HeightMap {
+    /// Parse lines of elevations (a-z), all of the same length, one of them being the start (S)
+    /// and another one the goal (E)
+    pub fn parse(data: &str) -> Result<Self> {
+        let cell = |c: char| (c.is_ascii_lowercase() || c == 'S' || c == 'E').then_some(c as u8);
+        let grid = Grid::parse(
+            Lines::new(Day12::DAY, data),
+            cell,
+            "elevation (a-z, S or E)",
+        )?;
+        Self::new(grid)
+    }
+
+    /// Find the start and goal in a grid of elevations
+    pub fn new(grid: Grid<u8>) -> Result<Self> {
+        let start = grid
             .position(|c| *c == b'S')
-            .expect("Start node not found");
-        let end_pos = data
-            .iter()
+            .context("Start position (S) not found")?;
+        let goal = grid
             .position(|c| *c == b'E')
-            .expect("End node not found");
+            .context("Goal position (E) not found")?;
 
-        Self {
-            data,
-            start: Pos(start_pos % W, start_pos / W),
-            goal: Pos(end_pos % W, end_pos / W),
-        }
+        Ok(Self { grid, start, goal })
+    }
+
+    /// The elevations, including the start (S) and goal (E) markers
+    pub fn grid(&self) -> &Grid<u8> {
+        &self.grid
+    }
+
+    pub fn start(&self) -> UPoint {
+        self.start
+    }
+
+    pub fn goal(&self) -> UPoint {
+        self.goal
     }
 
-    pub fn shortest_path(&self) -> Option<(Vec<Pos>, usize)> {
+    pub fn shortest_path(&self) -> Option<(Vec<UPoint>, usize)> {
         self.shortest_path_from(self.start)
     }
 
-    pub fn shortest_path_from_any_pos(&self) -> Option<(Vec<Pos>, usize)> {
+    pub fn shortest_path_from_any_pos(&self) -> Option<(Vec<UPoint>, usize)> {
         let starting_positions = self.starting_positions();
         let mut shortest_paths = starting_positions
             .into_iter()
@@ -93,7 +96,7 @@ impl<const W: usize, const H: usize> Grid<W, H> {
         shortest_paths.first().cloned()
     }
 
-    fn shortest_path_from(&self, p: Pos) -> Option<(Vec<Pos>, usize)> {
+    pub fn shortest_path_from(&self, p: UPoint) -> Option<(Vec<UPoint>, usize)> {
         pathfinding::prelude::astar(
             &p,
             |p| self.successors(*p),
@@ -102,15 +105,15 @@ impl<const W: usize, const H: usize> Grid<W, H> {
         )
     }
 
-    pub fn starting_positions(&self) -> Vec<Pos> {
-        (0..W * H)
-            .map(|i| Pos(i % W, i / W))
+    pub fn starting_positions(&self) -> Vec<UPoint> {
+        self.grid
+            .positions()
             .filter(|pos| self.value(*pos) == b'a')
             .collect()
     }
 
-    pub fn value(&self, Pos(x, y): Pos) -> u8 {
-        let v = self.data[y * W + x];
+    pub fn value(&self, pos: UPoint) -> u8 {
+        let v = self.grid[pos];
         if v == b'S' {
             b'a'
         } else if v == b'E' {
@@ -120,21 +123,22 @@ impl<const W: usize, const H: usize> Grid<W, H> {
         }
     }
 
-    pub fn distance(&self, Pos(x, y): Pos) -> usize {
-        let Pos(end_x, end_y) = self.goal;
-        (x.abs_diff(end_x)) + y.abs_diff(end_y)
+    pub fn distance(&self, pos: UPoint) -> usize {
+        pos.manhattan(self.goal)
     }
 
-    fn successors(&self, pos: Pos) -> Vec<(Pos, usize)> {
-        let candidates = pos.neighbours();
-        candidates
-            .into_iter()
-            .filter(|p| p.0 < W && p.1 < H && self.value(*p) <= self.value(pos) + 1)
-            .map(|p| (p, 1))
-            .collect()
+    /// The positions that can be reached in one step: at most one elevation higher
+    pub fn neighbours(&self, pos: UPoint) -> impl Iterator<Item = UPoint> + '_ {
+        self.grid
+            .neighbours4(pos)
+            .filter(move |p| self.value(*p) <= self.value(pos) + 1)
     }
 
-    fn success(&self, pos: Pos) -> bool {
+    fn successors(&self, pos: UPoint) -> Vec<(UPoint, usize)> {
+        self.neighbours(pos).map(|p| (p, 1)).collect()
+    }
+
+    fn success(&self, pos: UPoint) -> bool {
         pos == self.goal
     }
 }
@@ -143,20 +147,22 @@ impl<const W: usize, const H: usize> Grid<W, H> {
 mod tests {
     use super::*;
 
+    fn example() -> HeightMap {
+        HeightMap::parse(include_str!("../examples/day12.txt")).unwrap()
+    }
+
     #[test]
     fn test_part1() {
-        let data = b"SabqponmabcryxxlaccszExkacctuvwjabdefghi".to_vec();
-        let grid = Grid::<8, 5>::new(data);
-        let (shortest_path, _cost) = grid.shortest_path().unwrap();
+        let map = example();
+        let (shortest_path, _cost) = map.shortest_path().unwrap();
 
         assert_eq!(31, shortest_path.len() - 1)
     }
 
     #[test]
     fn test_part2() {
-        let data = b"SabqponmabcryxxlaccszExkacctuvwjabdefghi".to_vec();
-        let grid = Grid::<8, 5>::new(data);
-        let (shortest_path, _cost) = grid.shortest_path_from_any_pos().unwrap();
+        let map = example();
+        let (shortest_path, _cost) = map.shortest_path_from_any_pos().unwrap();
 
         assert_eq!(29, shortest_path.len() - 1)
     }
@@ -2,40 +2,50 @@ use std::{cmp::Ordering, iter::once};
 
 use eyre::Result;
 use nom::{
-    branch::alt, character::complete::char, combinator::map, multi::separated_list0,
-    sequence::delimited, Finish, IResult,
+    branch::alt,
+    character::complete::char,
+    combinator::{cut, map, opt},
+    error::{Error, ErrorKind},
+    multi::many0,
+    sequence::{delimited, pair, preceded},
+    Finish, IResult,
 };
 use once_cell::sync::Lazy;
 
-pub fn run() -> Result<()> {
-    let data = std::fs::read_to_string("inputs/day13.txt")?;
+use crate::{
+    parse::{Line, Lines, ParseError},
+    solution::{Answer, Solution},
+};
 
-    let packet_pairs = parse_packets(&data)?;
-    let sum = count_ordered_packet_pairs(&packet_pairs);
+pub struct Day13;
 
-    println!("Part 1: sum of indices = {sum}");
+impl Solution for Day13 {
+    const DAY: u8 = 13;
 
-    let mut packets = packet_pairs
-        .into_iter()
-        .flat_map(|(p1, p2)| once(p1).chain(once(p2)))
-        .chain(once(DELIM1.clone()))
-        .chain(once(DELIM2.clone()))
-        .collect::<Vec<_>>();
+    type Input = Vec<(Value, Value)>;
 
-    packets.sort();
-    let pos1 = packets.iter().position(|p| p == &*DELIM1).unwrap();
-    let pos2 = packets.iter().position(|p| p == &*DELIM2).unwrap();
-    let key = (pos1 + 1) * (pos2 + 1);
+    fn parse(data: &str) -> Result<Self::Input> {
+        Ok(parse_packets(data)?)
+    }
 
-    println!("Part 2: decoder key = {key}");
+    fn part1(packet_pairs: &Self::Input) -> Result<Answer> {
+        count_ordered_packet_pairs(packet_pairs).try_into()
+    }
 
-    Ok(())
+    fn part2(packet_pairs: &Self::Input) -> Result<Answer> {
+        decoder_key(packet_pairs).try_into()
+    }
 }
 
+/// How deep lists can be nested in a packet, so that parsing, comparing and dropping packets
+/// can't overflow the stack
+pub const MAX_DEPTH: usize = 100;
+
 static DELIM1: Lazy<Value> = Lazy::new(|| Value::List(vec![Value::list_from(2)]));
 static DELIM2: Lazy<Value> = Lazy::new(|| Value::List(vec![Value::list_from(6)]));
 
-fn count_ordered_packet_pairs(packet_pairs: &[(Value, Value)]) -> usize {
+/// Sum of the (1-based) indices of the pairs that are in the right order
+pub fn count_ordered_packet_pairs(packet_pairs: &[(Value, Value)]) -> usize {
     packet_pairs
         .iter()
         .enumerate()
@@ -43,43 +53,81 @@ fn count_ordered_packet_pairs(packet_pairs: &[(Value, Value)]) -> usize {
         .sum::<usize>()
 }
 
-fn parse_packets(data: &str) -> Result<Vec<(Value, Value)>> {
-    let lines = data.lines().collect::<Vec<_>>();
-    lines
-        .chunks(3)
-        .into_iter()
-        .map(|lines| {
-            let p1 = parse_packet(lines[0])?;
-            let p2 = parse_packet(lines[1])?;
-            Ok((p1, p2))
-        })
-        .collect()
+/// Parse the pairs of packets, separated by empty lines
+pub fn parse_packets(data: &str) -> Result<Vec<(Value, Value)>, ParseError> {
+    let mut lines = Lines::new(Day13::DAY, data);
+    let mut pairs = Vec::new();
+    while let Some(line) = lines.next() {
+        let p1 = parse_packet(&line)?;
+        let p2 = parse_packet(&lines.expect_line("the second packet of the pair")?)?;
+        pairs.push((p1, p2));
+
+        if let Some(line) = lines.next() {
+            if !line.text.is_empty() {
+                return Err(line.error_at(0, "an empty line between pairs of packets"));
+            }
+        }
+    }
+    Ok(pairs)
+}
+
+/// Find where the two divider packets would end up if sorted along with all the packets, and
+/// multiply their (1-based) indices
+pub fn decoder_key(packet_pairs: &[(Value, Value)]) -> usize {
+    let packets = packet_pairs.iter().flat_map(|(p1, p2)| [p1, p2]);
+    // Each divider comes after the packets smaller than it, the second one also after the first one
+    let pos1 = packets.clone().filter(|p| **p < *DELIM1).count() + 1;
+    let pos2 = packets.filter(|p| **p < *DELIM2).count() + 2;
+    pos1 * pos2
 }
 
 fn parse_int(s: &str) -> IResult<&str, Value> {
     map(nom::character::complete::u32, Value::Integer)(s)
 }
 
-fn parse_list(s: &str) -> IResult<&str, Value> {
-    map(
-        delimited(
-            char('['),
-            separated_list0(char(','), parse_value),
-            char(']'),
-        ),
-        Value::List,
-    )(s)
+/// Parse a list inside `depth` others
+fn parse_list(s: &str, depth: usize) -> IResult<&str, Value> {
+    if depth == MAX_DEPTH && s.starts_with('[') {
+        return Err(nom::Err::Failure(Error::new(s, ErrorKind::TooLarge)));
+    }
+    let value = |s| parse_nested(s, depth + 1);
+    // Once a comma has been read, another value must follow
+    let values = pair(value, many0(preceded(char(','), cut(value))));
+    map(delimited(char('['), opt(values), char(']')), |values| {
+        Value::List(
+            values
+                .map(|(first, rest)| once(first).chain(rest).collect())
+                .unwrap_or_default(),
+        )
+    })(s)
+}
+
+fn parse_nested(s: &str, depth: usize) -> IResult<&str, Value> {
+    alt((parse_int, |s| parse_list(s, depth)))(s)
 }
 
-fn parse_value(s: &str) -> IResult<&str, Value> {
-    alt((parse_int, parse_list))(s)
+pub fn parse_value(s: &str) -> IResult<&str, Value> {
+    parse_nested(s, 0)
 }
 
-fn parse_packet(s: &str) -> Result<Value> {
-    let (_rest, res) = parse_value(s)
-        .finish()
-        .map_err(|_| eyre::eyre!("Parse error"))?;
-    Ok(res)
+pub fn parse_packet(line: &Line) -> Result<Value, ParseError> {
+    let (rest, value) = parse_value(line.text).finish().map_err(|e: Error<&str>| {
+        // nom only tells where it stopped, what was expected there depends on what precedes
+        let offset = line.text.len() - e.input.len();
+        if e.code == ErrorKind::TooLarge {
+            return line.error_at(offset, format!("at most {MAX_DEPTH} nested lists"));
+        }
+        let expected = match line.text[..offset].chars().last() {
+            None | Some(',') => "a list or an integer",
+            Some('[') => "a list, an integer or ']'",
+            Some(_) => "',' or ']'",
+        };
+        line.error_at(offset, expected)
+    })?;
+    if !rest.is_empty() {
+        return Err(line.error_at(line.text.len() - rest.len(), "end of line"));
+    }
+    Ok(value)
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -96,19 +144,18 @@ impl Value {
 
 impl PartialOrd for Value {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let res = match (self, other) {
-            (Value::Integer(a), Value::Integer(b)) => a.cmp(b),
-            (Value::Integer(v), Value::List(_)) => Self::list_from(*v).cmp(other),
-            (Value::List(_), Value::Integer(v)) => self.cmp(&Self::list_from(*v)),
-            (Value::List(l1), Value::List(l2)) => l1.cmp(l2),
-        };
-        Some(res)
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for Value {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
+        match (self, other) {
+            (Value::Integer(a), Value::Integer(b)) => a.cmp(b),
+            (Value::Integer(v), Value::List(_)) => Self::list_from(*v).cmp(other),
+            (Value::List(_), Value::Integer(v)) => self.cmp(&Self::list_from(*v)),
+            (Value::List(l1), Value::List(l2)) => l1.cmp(l2),
+        }
     }
 }
 
@@ -116,72 +163,90 @@ impl Ord for Value {
 mod tests {
     use super::*;
 
+    fn packet(text: &str) -> Value {
+        let line = Line {
+            day: Day13::DAY,
+            number: 1,
+            text,
+        };
+        parse_packet(&line).unwrap()
+    }
+
     #[test]
     fn test_cmp() {
-        let p1 = parse_packet("[1,1,3,1,1]").unwrap();
-        let p2 = parse_packet("[1,1,5,1,1]").unwrap();
+        let p1 = packet("[1,1,3,1,1]");
+        let p2 = packet("[1,1,5,1,1]");
         assert!(p1 <= p2);
 
-        let p1 = parse_packet("[[1],[2,3,4]]").unwrap();
-        let p2 = parse_packet("[[1],4]").unwrap();
+        let p1 = packet("[[1],[2,3,4]]");
+        let p2 = packet("[[1],4]");
         assert!(p1 <= p2);
 
-        let p1 = parse_packet("[9]").unwrap();
-        let p2 = parse_packet("[[8,7,6]]").unwrap();
+        let p1 = packet("[9]");
+        let p2 = packet("[[8,7,6]]");
         assert!(p1 > p2);
 
-        let p1 = parse_packet("[[4,4],4,4]").unwrap();
-        let p2 = parse_packet("[[4,4],4,4,4]").unwrap();
+        let p1 = packet("[[4,4],4,4]");
+        let p2 = packet("[[4,4],4,4,4]");
         assert!(p1 <= p2);
 
-        let p1 = parse_packet("[7,7,7,7]").unwrap();
-        let p2 = parse_packet("[7,7,7]").unwrap();
+        let p1 = packet("[7,7,7,7]");
+        let p2 = packet("[7,7,7]");
         assert!(p1 > p2);
 
-        let p1 = parse_packet("[]").unwrap();
-        let p2 = parse_packet("[3]").unwrap();
+        let p1 = packet("[]");
+        let p2 = packet("[3]");
         assert!(p1 <= p2);
 
-        let p1 = parse_packet("[[[]]]").unwrap();
-        let p2 = parse_packet("[[]]").unwrap();
+        let p1 = packet("[[[]]]");
+        let p2 = packet("[[]]");
         assert!(p1 > p2);
 
-        let p1 = parse_packet("[5,6,7]").unwrap();
-        let p2 = parse_packet("[5,6,0]").unwrap();
+        let p1 = packet("[5,6,7]");
+        let p2 = packet("[5,6,0]");
         assert!(p1 > p2);
 
-        let p1 = parse_packet("[1,[2,[3,[4,[5,6,7]]]],8,9]").unwrap();
-        let p2 = parse_packet("[1,[2,[3,[4,[5,6,0]]]],8,9]").unwrap();
+        let p1 = packet("[1,[2,[3,[4,[5,6,7]]]],8,9]");
+        let p2 = packet("[1,[2,[3,[4,[5,6,0]]]],8,9]");
         assert!(p1 > p2);
     }
 
     #[test]
     fn test_part1() {
-        let data = r"[1,1,3,1,1]
-[1,1,5,1,1]
-
-[[1],[2,3,4]]
-[[1],4]
-
-[9]
-[[8,7,6]]
-
-[[4,4],4,4]
-[[4,4],4,4,4]
-
-[7,7,7,7]
-[7,7,7]
-
-[]
-[3]
-
-[[[]]]
-[[]]
-
-[1,[2,[3,[4,[5,6,7]]]],8,9]
-[1,[2,[3,[4,[5,6,0]]]],8,9]";
+        let data = include_str!("../examples/day13.txt");
         let packet_pairs = parse_packets(data).unwrap();
         let sum = count_ordered_packet_pairs(&packet_pairs);
         assert_eq!(13, sum);
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |data: &str| {
+            let e = parse_packets(data).unwrap_err();
+            (e.line, e.column, e.expected)
+        };
+        assert_eq!(error("[1,[2,x]]"), (1, 7, "a list or an integer".into()));
+        assert_eq!(error("[1]\n[1 2]"), (2, 3, "',' or ']'".into()));
+        assert_eq!(error("[1]\n[]]"), (2, 3, "end of line".into()));
+        assert_eq!(
+            error("[1]\n[2]\n[3]"),
+            (3, 1, "an empty line between pairs of packets".into())
+        );
+        assert_eq!(
+            error("[1]\n[2]\n\n[3]"),
+            (5, 1, "the second packet of the pair".into())
+        );
+    }
+
+    #[test]
+    fn test_nesting_depth() {
+        let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        let deepest = nested(MAX_DEPTH);
+        assert!(parse_packets(&format!("{deepest}\n{deepest}")).is_ok());
+
+        // Deep enough to overflow the stack if the depth wasn't limited
+        let e = parse_packets(&format!("[]\n{}", nested(100_000))).unwrap_err();
+        assert_eq!((e.line, e.column), (2, MAX_DEPTH + 1));
+        assert_eq!(e.expected, format!("at most {MAX_DEPTH} nested lists"));
+    }
 }
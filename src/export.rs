@@ -0,0 +1,314 @@
+//! Pictures of grid-shaped states saved as PNG or SVG images, for instance for reports. Each cell
+//! of the grid becomes a square of the colour the caller picks for it. The final states of days
+//! 8, 9, 10 and 12 can be saved this way, see [`export_day`].
+
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use eyre::{bail, eyre, Result, WrapErr};
+use itertools::Itertools;
+
+use crate::{
+    day08::Day08,
+    day09::{self, Day09},
+    day10::{Cpu, Day10},
+    day12::Day12,
+    geometry::{BoundingBox, IPoint},
+    grid::Grid,
+    solution::{Part, Solution},
+};
+
+/// The days that can be exported
+pub const DAYS: [u8; 4] = [8, 9, 10, 12];
+
+/// A colour, as red, green and blue intensities
+pub type Rgb = [u8; 3];
+
+/// The colour a fraction `t` (between 0 and 1) of the way from `from` to `to`
+pub fn gradient(from: Rgb, to: Rgb, t: f64) -> Rgb {
+    let t = t.clamp(0.0, 1.0);
+    let mut colour = from;
+    for (c, (&a, &b)) in colour.iter_mut().zip(from.iter().zip(&to)) {
+        *c = (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    }
+    colour
+}
+
+/// The supported image formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    /// The format matching the extension of the file
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let extension = path.extension().and_then(|e| e.to_str());
+        match extension.map(str::to_ascii_lowercase).as_deref() {
+            Some("png") => Ok(Self::Png),
+            Some("svg") => Ok(Self::Svg),
+            _ => bail!(
+                "Can't tell the image format of {}: its extension must be .png or .svg",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// Save the grid as an image file in the format matching its extension, each cell being a
+/// `scale`x`scale` square of the colour given by `colour`
+pub fn save<T>(
+    grid: &Grid<T>,
+    colour: impl Fn(&T) -> Rgb,
+    scale: usize,
+    path: &Path,
+) -> Result<()> {
+    let format = ImageFormat::from_path(path)?;
+    let file =
+        File::create(path).wrap_err_with(|| format!("Failed to create {}", path.display()))?;
+    let mut out = BufWriter::new(file);
+    match format {
+        ImageFormat::Png => write_png(grid, colour, scale, &mut out)?,
+        ImageFormat::Svg => write_svg(grid, colour, scale, &mut out)?,
+    }
+    out.flush()
+        .wrap_err_with(|| format!("Failed to write {}", path.display()))
+}
+
+pub fn write_png<T>(
+    grid: &Grid<T>,
+    colour: impl Fn(&T) -> Rgb,
+    scale: usize,
+    out: impl Write,
+) -> Result<()> {
+    let (width, height) = (grid.width() * scale, grid.height() * scale);
+    if width == 0 || height == 0 {
+        bail!("Can't save an empty image as PNG");
+    }
+    let mut pixels = Vec::with_capacity(width * height * 3);
+    for row in grid.rows() {
+        let line = row
+            .iter()
+            .flat_map(|cell| std::iter::repeat_n(colour(cell), scale))
+            .flatten()
+            .collect_vec();
+        for _ in 0..scale {
+            pixels.extend(&line);
+        }
+    }
+
+    let size = |n: usize| u32::try_from(n).map_err(|_| eyre!("The image is too large for PNG"));
+    let mut encoder = png::Encoder::new(out, size(width)?, size(height)?);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&pixels)?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// Write the grid as an SVG image, merging the cells of a row that have the same colour
+pub fn write_svg<T>(
+    grid: &Grid<T>,
+    colour: impl Fn(&T) -> Rgb,
+    scale: usize,
+    mut out: impl Write,
+) -> Result<()> {
+    let (width, height) = (grid.width(), grid.height());
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {width} {height}" shape-rendering="crispEdges">"#,
+        width * scale,
+        height * scale,
+    )?;
+    for (y, row) in grid.rows().enumerate() {
+        let mut x = 0;
+        for (colour, run) in &row.iter().map(&colour).group_by(|c| *c) {
+            let len = run.count();
+            let [r, g, b] = colour;
+            writeln!(
+                out,
+                r##"<rect x="{x}" y="{y}" width="{len}" height="1" fill="#{r:02x}{g:02x}{b:02x}"/>"##
+            )?;
+            x += len;
+        }
+    }
+    writeln!(out, "</svg>")?;
+    Ok(())
+}
+
+/// Save a picture of the state of the given day and part once solved, from its input
+pub fn export_day(day: u8, data: &str, part: Part, scale: usize, path: &Path) -> Result<()> {
+    match day {
+        8 => day08(data, scale, path),
+        9 => day09(data, part, scale, path),
+        10 => day10(data, scale, path),
+        12 => day12(data, part, scale, path),
+        _ => bail!(
+            "Day {day} can't be exported, only days {} can",
+            DAYS.iter().join(", ")
+        ),
+    }
+}
+
+/// The heights of the trees, those visible from outside the forest being in warm colours and the
+/// others in green, both getting lighter as the trees get higher
+fn day08(data: &str, scale: usize, path: &Path) -> Result<()> {
+    let forest = Day08::parse(data)?;
+    let trees = forest.grid();
+    let cells = Grid::from_fn(trees.width(), trees.height(), |p| {
+        (trees[p], forest.is_visible(p.x, p.y))
+    });
+    let colour = |&(height, visible): &(u8, bool)| {
+        let t = height as f64 / 9.0;
+        match visible {
+            true => gradient([120, 40, 0], [255, 220, 80], t),
+            false => gradient([10, 40, 10], [120, 200, 110], t),
+        }
+    };
+    save(&cells, colour, scale, path)
+}
+
+/// The positions visited by the tail of the rope, the starting one being in red
+fn day09(data: &str, part: Part, scale: usize, path: &Path) -> Result<()> {
+    let moves = Day09::parse(data)?;
+    let visited = match part {
+        Part::One => rope::<1>(&moves),
+        Part::Two => rope::<9>(&moves),
+    };
+    let start = IPoint::default();
+    let bbox = BoundingBox::of(visited.iter().copied()).unwrap_or(BoundingBox::new(start));
+    let cells = Grid::from_fn(bbox.width(), bbox.height(), |p| {
+        let pos = bbox.min + IPoint::new(p.x as isize, p.y as isize);
+        match (pos == start, visited.contains(&pos)) {
+            (true, _) => [220, 40, 40],
+            (false, true) => [40, 90, 200],
+            (false, false) => [250, 250, 245],
+        }
+    });
+    save(&cells, |&colour| colour, scale, path)
+}
+
+fn rope<const N: usize>(moves: &day09::Moves) -> HashSet<IPoint> {
+    let mut rope = day09::Grid::<N>::new();
+    rope.apply(moves);
+    rope.tail_positions().clone()
+}
+
+/// The screen drawn by the CPU, with amber pixels on a dark background
+fn day10(data: &str, scale: usize, path: &Path) -> Result<()> {
+    let mut cpu = Cpu::new();
+    cpu.run(&Day10::parse(data)?)?;
+    let crt = cpu.crt();
+    let pixels = Grid::from_fn(40, 6, |p| crt.is_lit(p.x, p.y));
+    let colour = |&lit: &bool| match lit {
+        true => [255, 190, 40],
+        false => [25, 25, 30],
+    };
+    save(&pixels, colour, scale, path)
+}
+
+/// The elevations, from green to white as they get higher, with the shortest path in red
+fn day12(data: &str, part: Part, scale: usize, path: &Path) -> Result<()> {
+    let map = Day12::parse(data)?;
+    let shortest_path = match part {
+        Part::One => map.shortest_path(),
+        Part::Two => map.shortest_path_from_any_pos(),
+    };
+    let route = shortest_path
+        .map(|(route, _)| route.into_iter().collect::<HashSet<_>>())
+        .unwrap_or_default();
+    let grid = map.grid();
+    let cells = Grid::from_fn(grid.width(), grid.height(), |p| {
+        (map.value(p), route.contains(&p))
+    });
+    let colour = |&(elevation, on_route): &(u8, bool)| match on_route {
+        true => [220, 30, 30],
+        false => gradient(
+            [30, 90, 50],
+            [240, 240, 230],
+            (elevation - b'a') as f64 / 25.0,
+        ),
+    };
+    save(&cells, colour, scale, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::UPoint;
+
+    fn checkerboard() -> Grid<bool> {
+        Grid::from_fn(3, 2, |p| (p.x + p.y) % 2 == 0)
+    }
+
+    fn colour(&black: &bool) -> Rgb {
+        if black {
+            [0, 0, 0]
+        } else {
+            [255, 255, 255]
+        }
+    }
+
+    #[test]
+    fn test_png() {
+        let mut data = Vec::new();
+        write_png(&checkerboard(), colour, 2, &mut data).unwrap();
+        let mut reader = png::Decoder::new(data.as_slice()).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (6, 4));
+        // Second row of pixels, still in the first row of cells
+        assert_eq!(
+            &pixels[18..36],
+            &[0, 0, 0, 0, 0, 0, 255, 255, 255, 255, 255, 255, 0, 0, 0, 0, 0, 0]
+        );
+
+        let empty = Grid::from_fn(0, 0, |_| false);
+        assert!(write_png(&empty, colour, 2, Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_svg() {
+        let mut grid = checkerboard();
+        grid[UPoint::new(0, 1)] = true;
+        grid[UPoint::new(2, 1)] = true;
+        let mut data = Vec::new();
+        write_svg(&grid, colour, 10, &mut data).unwrap();
+        let svg = String::from_utf8(data).unwrap();
+        assert!(svg.contains(r#"width="30" height="20" viewBox="0 0 3 2""#));
+        assert_eq!(svg.matches("<rect").count(), 4);
+        assert!(svg.contains(r##"<rect x="0" y="1" width="3" height="1" fill="#000000"/>"##));
+    }
+
+    #[test]
+    fn test_export_days() {
+        let dir = tempfile::tempdir().unwrap();
+        for day in DAYS {
+            let data = std::fs::read_to_string(format!(
+                "{}/examples/day{day:02}.txt",
+                env!("CARGO_MANIFEST_DIR")
+            ))
+            .unwrap();
+            for extension in ["png", "svg"] {
+                let path = dir.path().join(format!("day{day:02}.{extension}"));
+                export_day(day, &data, Part::Two, 4, &path).unwrap();
+                assert!(path.metadata().unwrap().len() > 0);
+            }
+        }
+        let path = dir.path().join("day01.png");
+        assert!(export_day(1, "", Part::One, 4, &path).is_err());
+        assert!(export_day(10, "noop", Part::One, 4, &dir.path().join("crt.jpg")).is_err());
+    }
+
+    #[test]
+    fn test_gradient() {
+        assert_eq!(gradient([0, 100, 200], [200, 100, 0], 0.25), [50, 100, 150]);
+        assert_eq!(gradient([0, 0, 0], [10, 10, 10], 2.0), [10, 10, 10]);
+    }
+}
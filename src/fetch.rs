@@ -0,0 +1,154 @@
+//! Download of the puzzle inputs from the Advent of Code website, or any server exposing the same
+//! routes
+
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use eyre::{bail, Result, WrapErr};
+
+use crate::input::{self, InputDirs};
+
+/// Environment variable holding the value of the `session` cookie of the website
+pub const SESSION_VAR: &str = "AOC_SESSION";
+/// Environment variable overriding [`DEFAULT_BASE_URL`]
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2022";
+/// Minimum delay between two requests to the server
+pub const MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// An HTTP client for the website, authenticated with a session token and never sending two
+/// requests less than `min_interval` apart
+pub struct Client {
+    base_url: String,
+    session: String,
+    agent: ureq::Agent,
+    min_interval: Duration,
+    last_request: Option<Instant>,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .user_agent(concat!("aoc2022/", env!("CARGO_PKG_VERSION")))
+            .build();
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            agent,
+            min_interval: MIN_INTERVAL,
+            last_request: None,
+        }
+    }
+
+    /// Build a client from `AOC_SESSION`, and from `AOC_BASE_URL` unless a base URL is given
+    pub fn from_env(base_url: Option<&str>) -> Result<Self> {
+        let session = match std::env::var(SESSION_VAR) {
+            Ok(session) if !session.trim().is_empty() => session,
+            _ => bail!("Set {SESSION_VAR} to the value of the session cookie of the website"),
+        };
+        let base_url = match base_url {
+            Some(url) => url.to_string(),
+            None => std::env::var(BASE_URL_VAR).unwrap_or_else(|_| DEFAULT_BASE_URL.to_string()),
+        };
+        Ok(Self::new(&base_url, session.trim()))
+    }
+
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// GET the given path (relative to the base URL) and return the body of the response
+    pub fn get(&mut self, path: &str) -> Result<String> {
+        let url = format!("{}/{path}", self.base_url);
+        self.throttle();
+        let request = self.agent.get(&url).set("Cookie", &self.cookie());
+        Self::body(&url, request.call())
+    }
+
+    /// POST the given form to the given path (relative to the base URL) and return the body of
+    /// the response
+    pub fn post_form(&mut self, path: &str, form: &[(&str, &str)]) -> Result<String> {
+        let url = format!("{}/{path}", self.base_url);
+        self.throttle();
+        let request = self.agent.post(&url).set("Cookie", &self.cookie());
+        Self::body(&url, request.send_form(form))
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.session)
+    }
+
+    /// Wait until `min_interval` has elapsed since the last request
+    fn throttle(&mut self) {
+        if let Some(last) = self.last_request {
+            if let Some(remaining) = self.min_interval.checked_sub(last.elapsed()) {
+                std::thread::sleep(remaining);
+            }
+        }
+        self.last_request = Some(Instant::now());
+    }
+
+    fn body(url: &str, response: Result<ureq::Response, ureq::Error>) -> Result<String> {
+        match response {
+            Ok(response) => response
+                .into_string()
+                .wrap_err_with(|| format!("Failed to read the response from {url}")),
+            Err(ureq::Error::Status(404, _)) => bail!("{url} was not found"),
+            Err(ureq::Error::Status(status @ (400 | 401 | 403), _)) => {
+                bail!("{url} rejected the session token (status {status}), check {SESSION_VAR}")
+            }
+            Err(ureq::Error::Status(429, response)) => {
+                let retry_after = response
+                    .header("Retry-After")
+                    .map_or(String::new(), |secs| format!(", retry after {secs}s"));
+                bail!("Too many requests to {url}{retry_after}")
+            }
+            Err(ureq::Error::Status(status, _)) => bail!("{url} failed with status {status}"),
+            Err(e) => Err(e).wrap_err_with(|| format!("Request to {url} failed")),
+        }
+    }
+}
+
+/// Where an input was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fetched {
+    /// Just downloaded to the given file
+    Downloaded(PathBuf),
+    /// Already present in the given file, so not downloaded again
+    Cached(PathBuf),
+}
+
+/// Download the input of the given day to the cache directory, unless one of the input
+/// directories already has it. Empty files, like the placeholders created by `new`, don't count.
+pub fn fetch_input(client: &mut Client, dirs: &InputDirs, day: u8) -> Result<Fetched> {
+    let existing = dirs
+        .candidates(day)
+        .into_iter()
+        .find(|path| path.metadata().is_ok_and(|m| m.len() > 0));
+    if let Some(path) = existing {
+        return Ok(Fetched::Cached(path));
+    }
+
+    let data = client
+        .get(&format!("day/{day}/input"))
+        .wrap_err_with(|| format!("Failed to download the input of day {day}"))?;
+
+    let dir = dirs.cache_dir();
+    std::fs::create_dir_all(dir).wrap_err_with(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(input::file_name(day));
+    // Write to a temporary file first so that an interrupted write doesn't leave a partial input
+    // that would then be considered cached
+    let tmp = path.with_extension("txt.tmp");
+    std::fs::write(&tmp, data)
+        .and_then(|_| std::fs::rename(&tmp, &path))
+        .wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+    Ok(Fetched::Downloaded(path))
+}
@@ -0,0 +1,487 @@
+//! Random puzzle inputs, larger or weirder than the real ones, to stress-test and benchmark the
+//! solutions. The same seed and parameters always give the same input.
+
+use std::collections::{btree_map::Entry, BTreeMap};
+
+use eyre::{bail, Result};
+use itertools::Itertools;
+use rand::{
+    seq::{IndexedRandom, SliceRandom},
+    Rng, SeedableRng,
+};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    day05::{self, Move, Stacks},
+    day11::{DivisibilityTest, Item, Monkey, Monkeys, Operation, Val},
+    day13,
+};
+
+/// The days that have a generator
+pub const DAYS: [u8; 13] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13];
+
+/// The size of the generated input. Each day only uses the parameters that make sense for it,
+/// and picks a default close to the real input for those that aren't set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params {
+    pub seed: u64,
+    /// Number of elements: elves (day 1), rounds (day 2), rucksacks (day 3), pairs (days 4 and
+    /// 13), moves (days 5 and 9), characters (day 6), directories (day 7), instructions (day 10)
+    /// or monkeys (day 11)
+    pub size: Option<usize>,
+    /// Width of the grid (days 8 and 12) or number of stacks (day 5)
+    pub width: Option<usize>,
+    /// Height of the grid (days 8 and 12) or maximum height of the starting stacks (day 5)
+    pub height: Option<usize>,
+    /// Maximum nesting depth of the packets (day 13)
+    pub depth: Option<usize>,
+    /// Number of rounds the monkeys can play with relief without their worry levels overflowing
+    /// (day 11)
+    pub rounds: Option<usize>,
+}
+
+/// Generate a valid input for the given day
+pub fn generate(day: u8, params: &Params) -> Result<String> {
+    let mut rng = ChaCha8Rng::seed_from_u64(params.seed);
+    let rng = &mut rng;
+    match day {
+        1 => Ok(day01(rng, params.size.unwrap_or(250))),
+        2 => Ok(day02(rng, params.size.unwrap_or(2500))),
+        3 => Ok(day03(rng, params.size.unwrap_or(300))),
+        4 => Ok(day04(rng, params.size.unwrap_or(1000))),
+        5 => day05(
+            rng,
+            params.width.unwrap_or(9),
+            params.height.unwrap_or(8),
+            params.size.unwrap_or(500),
+        ),
+        6 => Ok(day06(rng, params.size.unwrap_or(4096))),
+        7 => Ok(day07(rng, params.size.unwrap_or(200))),
+        8 => day08(rng, params.width.unwrap_or(99), params.height.unwrap_or(99)),
+        9 => Ok(day09(rng, params.size.unwrap_or(2000))),
+        10 => Ok(day10(rng, params.size.unwrap_or(140))),
+        11 => day11(rng, params.size.unwrap_or(8), params.rounds.unwrap_or(20)),
+        12 => day12(
+            rng,
+            params.width.unwrap_or(161),
+            params.height.unwrap_or(41),
+        ),
+        13 => day13(rng, params.size.unwrap_or(150), params.depth.unwrap_or(4)),
+        _ => bail!("No generator for day {day}"),
+    }
+}
+
+fn lowercase(rng: &mut ChaCha8Rng) -> char {
+    rng.random_range('a'..='z')
+}
+
+/// Elves carrying a few snacks each
+fn day01(rng: &mut ChaCha8Rng, elves: usize) -> String {
+    (0..elves)
+        .map(|_| {
+            let snacks = rng.random_range(1..=15);
+            (0..snacks)
+                .map(|_| format!("{}\n", rng.random_range(1000..=60000)))
+                .collect::<String>()
+        })
+        .join("\n")
+}
+
+fn day02(rng: &mut ChaCha8Rng, rounds: usize) -> String {
+    (0..rounds)
+        .map(|_| {
+            let opponent = rng.random_range('A'..='C');
+            let me = rng.random_range('X'..='Z');
+            format!("{opponent} {me}\n")
+        })
+        .collect()
+}
+
+/// Groups of 3 rucksacks (so the number is rounded up to a multiple of 3). Each rucksack has
+/// a single item in both compartments, and each group a single item common to all 3 rucksacks.
+fn day03(rng: &mut ChaCha8Rng, rucksacks: usize) -> String {
+    let mut items = ('a'..='z').chain('A'..='Z').collect::<Vec<_>>();
+    let mut text = String::new();
+    for _ in 0..rucksacks.div_ceil(3) {
+        items.shuffle(rng);
+        let (badge, others) = items.split_first().expect("52 items");
+        // The rucksacks of the group share no other item than the badge
+        for own in others.chunks(others.len() / 3) {
+            let (common, own) = own.split_first().expect("17 items per rucksack");
+            let (left, right) = own.split_at(own.len() / 2);
+            let len = rng.random_range(2..=16);
+            let mut compartments = [left, right].map(|items| {
+                let mut compartment = (0..len - 1)
+                    .map(|_| *items.choose(rng).expect("8 items per compartment"))
+                    .collect::<Vec<_>>();
+                compartment.insert(rng.random_range(0..len), *common);
+                compartment
+            });
+            let compartment = &mut compartments[rng.random_range(0..2)];
+            let i = compartment
+                .iter()
+                .position(|c| c != common)
+                .expect("2 items");
+            compartment[i] = *badge;
+            text.extend(compartments.iter().flatten());
+            text.push('\n');
+        }
+    }
+    text
+}
+
+fn day04(rng: &mut ChaCha8Rng, pairs: usize) -> String {
+    let mut range = || {
+        let start = rng.random_range(1..=99);
+        let end = rng.random_range(start..=99);
+        format!("{start}-{end}")
+    };
+    (0..pairs)
+        .map(|_| format!("{},{}\n", range(), range()))
+        .collect()
+}
+
+/// Moves that are all possible, simulated on the starting stacks
+fn day05(rng: &mut ChaCha8Rng, num_stacks: usize, height: usize, moves: usize) -> Result<String> {
+    if !(1..=9).contains(&num_stacks) {
+        bail!("There must be between 1 and 9 stacks");
+    }
+    let stacks = (0..num_stacks)
+        .map(|_| {
+            let len = rng.random_range(0..=height);
+            (0..len)
+                .map(|_| rng.random_range('A'..='Z') as u8)
+                .collect_vec()
+        })
+        .collect_vec();
+
+    let mut heights = stacks.iter().map(Vec::len).collect_vec();
+    let mut procedure = Vec::new();
+    for _ in 0..moves {
+        let non_empty = (0..num_stacks).filter(|i| heights[*i] > 0).collect_vec();
+        let Some(&from) = non_empty.choose(rng) else {
+            break;
+        };
+        let to = match num_stacks {
+            1 => from,
+            _ => (rng.random_range(1..num_stacks) + from) % num_stacks,
+        };
+        let num = rng.random_range(1..=heights[from]);
+        heights[from] -= num;
+        heights[to] += num;
+        procedure.push(Move(num, from + 1, to + 1));
+    }
+    Ok(day05::render_procedure(&Stacks::from(stacks), &procedure))
+}
+
+/// A datastream made of only 3 different characters, except for its last 14 characters which
+/// are all different: both markers are found at its very end
+fn day06(rng: &mut ChaCha8Rng, len: usize) -> String {
+    let mut text = (0..len.saturating_sub(14))
+        .map(|_| rng.random_range('a'..='c'))
+        .collect::<String>();
+    let mut marker = ('a'..='z').collect_vec();
+    marker.shuffle(rng);
+    text.extend(&marker[..14]);
+    text.push('\n');
+    text
+}
+
+/// A directory and what it contains
+#[derive(Default)]
+struct Dir {
+    dirs: BTreeMap<String, usize>,
+    files: BTreeMap<String, usize>,
+}
+
+/// A file system using between 40M and 70M of the disk, so that a directory has to be deleted,
+/// explored in depth first order
+fn day07(rng: &mut ChaCha8Rng, num_dirs: usize) -> String {
+    fn name(rng: &mut ChaCha8Rng, taken: impl Fn(&str) -> bool) -> String {
+        loop {
+            let len = rng.random_range(1..=8);
+            let name = (0..len).map(|_| lowercase(rng)).collect::<String>();
+            if !taken(&name) {
+                break name;
+            }
+        }
+    }
+
+    let mut dirs = vec![Dir::default()];
+    for i in 1..num_dirs.max(1) {
+        let parent = rng.random_range(0..i);
+        let dir_name = name(rng, |n| dirs[parent].dirs.contains_key(n));
+        dirs[parent].dirs.insert(dir_name, i);
+        dirs.push(Dir::default());
+    }
+    // Split the used space between the files according to random weights
+    let mut total_weight = 0;
+    for (i, dir) in dirs.iter_mut().enumerate() {
+        let num_files = rng.random_range(usize::from(i == 0)..=4);
+        while dir.files.len() < num_files {
+            let mut file_name = name(rng, |_| false);
+            if rng.random_bool(0.5) {
+                file_name = format!("{file_name}.{}", name(rng, |_| false));
+            }
+            if let Entry::Vacant(entry) = dir.files.entry(file_name) {
+                let weight = rng.random_range(1..=1000);
+                total_weight += weight;
+                entry.insert(weight);
+            }
+        }
+    }
+    let used = rng.random_range(40_000_001..70_000_000);
+    let mut remaining = used;
+    for dir in &mut dirs {
+        for size in dir.files.values_mut() {
+            *size = *size * used / total_weight;
+            remaining -= *size;
+        }
+    }
+    // What the rounding lost goes to a file of the root
+    *dirs[0]
+        .files
+        .values_mut()
+        .next()
+        .expect("the root has a file") += remaining;
+
+    fn explore(dirs: &[Dir], dir: usize, text: &mut String) {
+        *text += "$ ls\n";
+        for name in dirs[dir].dirs.keys() {
+            *text += &format!("dir {name}\n");
+        }
+        for (name, size) in &dirs[dir].files {
+            *text += &format!("{size} {name}\n");
+        }
+        for (name, subdir) in &dirs[dir].dirs {
+            *text += &format!("$ cd {name}\n");
+            explore(dirs, *subdir, text);
+            *text += "$ cd ..\n";
+        }
+    }
+    let mut text = "$ cd /\n".to_string();
+    explore(&dirs, 0, &mut text);
+    text
+}
+
+fn day08(rng: &mut ChaCha8Rng, width: usize, height: usize) -> Result<String> {
+    if width == 0 || height == 0 {
+        bail!("The forest must be at least 1 tree wide and high");
+    }
+    Ok((0..height)
+        .map(|_| {
+            let row = (0..width)
+                .map(|_| rng.random_range('0'..='9'))
+                .collect::<String>();
+            row + "\n"
+        })
+        .collect())
+}
+
+fn day09(rng: &mut ChaCha8Rng, moves: usize) -> String {
+    (0..moves)
+        .map(|_| {
+            let dir = ['U', 'D', 'L', 'R'].choose(rng).expect("4 directions");
+            format!("{dir} {}\n", rng.random_range(1..=20))
+        })
+        .collect()
+}
+
+/// A program lasting at least the 240 cycles drawn on the screen, keeping the sprite on it
+fn day10(rng: &mut ChaCha8Rng, instructions: usize) -> String {
+    let mut text = String::new();
+    let (mut x, mut cycles) = (1, 0);
+    for i in 0.. {
+        if i >= instructions && cycles >= 240 {
+            break;
+        }
+        if rng.random_bool(0.3) {
+            text += "noop\n";
+            cycles += 1;
+        } else {
+            let v = rng.random_range(-10..=10);
+            let v = if (-1..=40).contains(&(x + v)) { v } else { -v };
+            x += v;
+            text += &format!("addx {v}\n");
+            cycles += 2;
+        }
+    }
+    text
+}
+
+/// Monkeys testing the divisibility by distinct primes, so that the product of the divisors (by
+/// which worry levels are reduced without relief) is small enough not to overflow. The monkeys
+/// are drawn again until they can play the given number of rounds with relief.
+fn day11(rng: &mut ChaCha8Rng, num_monkeys: usize, rounds: usize) -> Result<String> {
+    const PRIMES: [usize; 9] = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+    const ATTEMPTS: usize = 1000;
+    if !(2..=PRIMES.len()).contains(&num_monkeys) {
+        bail!("There must be between 2 and {} monkeys", PRIMES.len());
+    }
+
+    for _ in 0..ATTEMPTS {
+        let mut divisors = PRIMES;
+        divisors.shuffle(rng);
+        let monkeys = (0..num_monkeys)
+            .map(|id| {
+                let num_items = rng.random_range(1..=8);
+                let starting_items = (0..num_items)
+                    .map(|_| Item(rng.random_range(50..=99)))
+                    .collect();
+                let operation = match rng.random_range(0..3) {
+                    0 => Operation::Mult(Val::Old, Val::Old),
+                    1 => Operation::Mult(Val::Old, Val::Literal(rng.random_range(2..=20))),
+                    _ => Operation::Add(Val::Old, Val::Literal(rng.random_range(1..=9))),
+                };
+                // Never throw to itself
+                let mut target = || (rng.random_range(1..num_monkeys) + id) % num_monkeys;
+                Monkey {
+                    id,
+                    starting_items,
+                    operation,
+                    div_test: DivisibilityTest(divisors[id]),
+                    throw_to_if_true: target(),
+                    throw_to_if_false: target(),
+                    num_inspections: 0,
+                }
+            })
+            .collect_vec();
+
+        let mut simulation = Monkeys(monkeys.clone());
+        if (0..rounds).all(|_| simulation.round(true).is_ok()) {
+            return Ok(monkeys.iter().join("\n"));
+        }
+    }
+    bail!("Failed to draw monkeys able to play {rounds} rounds in {ATTEMPTS} attempts")
+}
+
+/// A random height map, except along a path climbing from the start in a corner of the left
+/// edge to the goal in the opposite corner, one elevation at a time
+fn day12(rng: &mut ChaCha8Rng, width: usize, height: usize) -> Result<String> {
+    const CLIMB: usize = (b'z' - b'a') as usize;
+    if width == 0 || height == 0 || width + height - 2 < CLIMB {
+        bail!("The grid is too small to climb from a to z: width + height must be at least 27");
+    }
+
+    let mut grid = (0..height)
+        .map(|_| (0..width).map(|_| lowercase(rng)).collect_vec())
+        .collect_vec();
+    let mut steps = [vec![(1, 0); width - 1], vec![(0, 1); height - 1]].concat();
+    steps.shuffle(rng);
+    let flip = rng.random_bool(0.5);
+    let (mut x, mut y) = (0, 0);
+    for (i, (dx, dy)) in steps.iter().enumerate() {
+        x += dx;
+        y += dy;
+        let row = if flip { height - 1 - y } else { y };
+        grid[row][x] = (b'a' + ((i + 1) * CLIMB / steps.len()) as u8) as char;
+    }
+    let (start_row, goal_row) = if flip {
+        (height - 1, 0)
+    } else {
+        (0, height - 1)
+    };
+    grid[start_row][0] = 'S';
+    grid[goal_row][width - 1] = 'E';
+
+    Ok(grid
+        .iter()
+        .map(|row| row.iter().collect::<String>() + "\n")
+        .collect())
+}
+
+/// Pairs of packets nested at most `depth` lists deep
+fn day13(rng: &mut ChaCha8Rng, pairs: usize, depth: usize) -> Result<String> {
+    fn list(rng: &mut ChaCha8Rng, depth: usize) -> String {
+        let len = rng.random_range(0..=5);
+        let values = (0..len).map(|_| {
+            if depth > 1 && rng.random_bool(0.3) {
+                list(rng, depth - 1)
+            } else {
+                rng.random_range(0..=10).to_string()
+            }
+        });
+        format!("[{}]", values.collect_vec().join(","))
+    }
+
+    if !(1..=day13::MAX_DEPTH).contains(&depth) {
+        bail!(
+            "Packets must be nested between 1 and {} lists deep",
+            day13::MAX_DEPTH
+        );
+    }
+    Ok((0..pairs)
+        .map(|_| format!("{}\n{}\n", list(rng, depth), list(rng, depth)))
+        .join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{find_day, solution::Part};
+
+    fn small(seed: u64) -> Params {
+        Params {
+            seed,
+            size: Some(20),
+            ..Params::default()
+        }
+    }
+
+    #[test]
+    fn test_inputs_are_valid() {
+        for day in DAYS {
+            let solution = find_day(day).unwrap();
+            let mut params = small(day.into());
+            if day == 11 {
+                params.size = Some(4);
+            }
+            let data = generate(day, &params).unwrap();
+            let parsed = solution
+                .parse(&data)
+                .unwrap_or_else(|e| panic!("Day {day}: {e:?}\n{data}"));
+            for part in Part::ALL {
+                if let Err(e) = parsed.solve(part) {
+                    panic!("Day {day} part {part}: {e:?}\n{data}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_seed() {
+        for day in [1, 7, 13] {
+            assert_eq!(
+                generate(day, &small(1)).unwrap(),
+                generate(day, &small(1)).unwrap()
+            );
+            assert_ne!(
+                generate(day, &small(1)).unwrap(),
+                generate(day, &small(2)).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        let params = |width, height| Params {
+            width: Some(width),
+            height: Some(height),
+            ..Params::default()
+        };
+        assert!(generate(5, &params(10, 8)).is_err());
+        assert!(generate(12, &params(10, 10)).is_err());
+        assert!(generate(12, &params(20, 7)).is_ok());
+        for (width, height) in [(0, 30), (30, 0)] {
+            assert!(generate(8, &params(width, height)).is_err());
+            assert!(generate(12, &params(width, height)).is_err());
+        }
+        let depth = |depth| Params {
+            depth: Some(depth),
+            ..Params::default()
+        };
+        assert!(generate(13, &depth(0)).is_err());
+        assert!(generate(13, &depth(day13::MAX_DEPTH + 1)).is_err());
+        assert!(generate(26, &Params::default()).is_err());
+    }
+}
@@ -0,0 +1,363 @@
+//! Points, directions and bounding boxes on the 2D plane, shared by the days moving things
+//! around. `x` grows to the right and `y` downwards, like the rows of the puzzle inputs.
+
+use std::{
+    fmt::{Debug, Display},
+    hash::Hash,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+/// The integer types points are made of
+pub trait Coord:
+    Copy + Ord + Hash + Default + Debug + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    /// The distance between two coordinates
+    fn distance(self, other: Self) -> usize;
+}
+
+macro_rules! impl_coord {
+    ($($t:ty),*) => {
+        $(
+            impl Coord for $t {
+                fn distance(self, other: Self) -> usize {
+                    self.abs_diff(other) as usize
+                }
+            }
+        )*
+    };
+}
+
+impl_coord!(isize, usize);
+
+/// A point, or the vector from the origin to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A point anywhere on the plane
+pub type IPoint = Point<isize>;
+
+/// A point right or below of the origin, such as a cell of a grid
+pub type UPoint = Point<usize>;
+
+impl<T: Coord> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    /// The number of horizontal and vertical steps to go from one point to the other
+    pub fn manhattan(self, other: Self) -> usize {
+        self.x.distance(other.x) + self.y.distance(other.y)
+    }
+
+    /// The number of steps to go from one point to the other, moving diagonally as well
+    pub fn chebyshev(self, other: Self) -> usize {
+        self.x.distance(other.x).max(self.y.distance(other.y))
+    }
+}
+
+impl IPoint {
+    /// The point one step away in the given direction
+    pub fn step(self, dir: impl Into<Dir8>) -> Self {
+        self + dir.into().offset()
+    }
+
+    /// The vector made of the signs of both coordinates, each being -1, 0 or 1
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
+    }
+}
+
+impl UPoint {
+    /// The point one step away in the given direction, unless it is left or above of the origin
+    pub fn step(self, dir: impl Into<Dir8>) -> Option<Self> {
+        self.checked_add(dir.into().offset())
+    }
+
+    /// Move by the given vector, unless it ends up left or above of the origin
+    pub fn checked_add(self, v: IPoint) -> Option<Self> {
+        Some(Self::new(
+            self.x.checked_add_signed(v.x)?,
+            self.y.checked_add_signed(v.y)?,
+        ))
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> From<Point<T>> for (T, T) {
+    fn from(p: Point<T>) -> Self {
+        (p.x, p.y)
+    }
+}
+
+impl TryFrom<IPoint> for UPoint {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(p: IPoint) -> Result<Self, Self::Error> {
+        Ok(Self::new(p.x.try_into()?, p.y.try_into()?))
+    }
+}
+
+impl TryFrom<UPoint> for IPoint {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(p: UPoint) -> Result<Self, Self::Error> {
+        Ok(Self::new(p.x.try_into()?, p.y.try_into()?))
+    }
+}
+
+impl<T: Display> Display for Point<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl<T: Coord> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Coord> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Coord> AddAssign for Point<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Coord> SubAssign for Point<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+/// Scaling by a factor
+impl<T: Coord> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for IPoint {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+/// One of the 4 directions along the axes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dir {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir {
+    /// All the directions, clockwise from up
+    pub const ALL: [Dir; 4] = [Dir::Up, Dir::Right, Dir::Down, Dir::Left];
+
+    /// The vector of a step in that direction
+    pub fn offset(self) -> IPoint {
+        Dir8::from(self).offset()
+    }
+
+    /// The direction after a quarter turn clockwise
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    /// The direction after a quarter turn counterclockwise
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+}
+
+/// One of the 8 directions along the axes and the diagonals
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dir8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Dir8 {
+    /// All the directions, clockwise from up
+    pub const ALL: [Dir8; 8] = [
+        Dir8::Up,
+        Dir8::UpRight,
+        Dir8::Right,
+        Dir8::DownRight,
+        Dir8::Down,
+        Dir8::DownLeft,
+        Dir8::Left,
+        Dir8::UpLeft,
+    ];
+
+    /// The vector of a step in that direction
+    pub fn offset(self) -> IPoint {
+        let (x, y) = match self {
+            Dir8::Up => (0, -1),
+            Dir8::UpRight => (1, -1),
+            Dir8::Right => (1, 0),
+            Dir8::DownRight => (1, 1),
+            Dir8::Down => (0, 1),
+            Dir8::DownLeft => (-1, 1),
+            Dir8::Left => (-1, 0),
+            Dir8::UpLeft => (-1, -1),
+        };
+        IPoint::new(x, y)
+    }
+
+    /// The direction after an eighth of a turn clockwise
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    /// The direction after an eighth of a turn counterclockwise
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+}
+
+impl From<Dir> for Dir8 {
+    fn from(dir: Dir) -> Self {
+        match dir {
+            Dir::Up => Dir8::Up,
+            Dir::Right => Dir8::Right,
+            Dir::Down => Dir8::Down,
+            Dir::Left => Dir8::Left,
+        }
+    }
+}
+
+/// The smallest rectangle containing some points, both corners included
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoundingBox<T> {
+    /// The top left corner
+    pub min: Point<T>,
+    /// The bottom right corner
+    pub max: Point<T>,
+}
+
+impl<T: Coord> BoundingBox<T> {
+    /// The box around a single point
+    pub fn new(p: Point<T>) -> Self {
+        Self { min: p, max: p }
+    }
+
+    /// The box around all the points, if there are any
+    pub fn of(points: impl IntoIterator<Item = Point<T>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let mut bbox = Self::new(points.next()?);
+        points.for_each(|p| bbox.extend(p));
+        Some(bbox)
+    }
+
+    /// Grow the box to contain the point
+    pub fn extend(&mut self, p: Point<T>) {
+        self.min = Point::new(self.min.x.min(p.x), self.min.y.min(p.y));
+        self.max = Point::new(self.max.x.max(p.x), self.max.y.max(p.y));
+    }
+
+    pub fn contains(&self, p: Point<T>) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    /// The number of columns in the box
+    pub fn width(&self) -> usize {
+        self.min.x.distance(self.max.x) + 1
+    }
+
+    /// The number of rows in the box
+    pub fn height(&self) -> usize {
+        self.min.y.distance(self.max.y) + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let (p, q) = (IPoint::new(1, -2), IPoint::new(-3, 4));
+        assert_eq!(p + q, IPoint::new(-2, 2));
+        assert_eq!(p - q, IPoint::new(4, -6));
+        assert_eq!(-p * 3, IPoint::new(-3, 6));
+        assert_eq!((q - p).signum(), IPoint::new(-1, 1));
+        assert_eq!(p.manhattan(q), 10);
+        assert_eq!(p.chebyshev(q), 6);
+
+        let u = UPoint::new(2, 0);
+        assert_eq!(u.step(Dir::Left), Some(UPoint::new(1, 0)));
+        assert_eq!(u.step(Dir8::UpLeft), None);
+        assert_eq!(u.checked_add(q), None);
+        assert!(UPoint::try_from(p).is_err());
+        assert_eq!(IPoint::try_from(u), Ok(IPoint::new(2, 0)));
+        assert_eq!(u.to_string(), "(2, 0)");
+    }
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(Dir::Up.turn_right(), Dir::Right);
+        assert_eq!(Dir::Up.turn_left(), Dir::Left);
+        assert_eq!(Dir::Left.opposite(), Dir::Right);
+        assert_eq!(Dir8::UpLeft.turn_right(), Dir8::Up);
+        assert_eq!(Dir8::Up.turn_left(), Dir8::UpLeft);
+        assert_eq!(Dir8::DownRight.opposite(), Dir8::UpLeft);
+        for dir in Dir8::ALL {
+            assert_eq!(dir.offset() + dir.opposite().offset(), IPoint::default());
+            assert_eq!(dir.turn_right().turn_left(), dir);
+        }
+        assert_eq!(
+            IPoint::default().step(Dir::Down).step(Dir8::DownRight),
+            IPoint::new(1, 2)
+        );
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        assert_eq!(BoundingBox::<isize>::of([]), None);
+        let points = [IPoint::new(1, -2), IPoint::new(-3, 4), IPoint::new(0, 0)];
+        let bbox = BoundingBox::of(points).unwrap();
+        assert_eq!(bbox.min, IPoint::new(-3, -2));
+        assert_eq!(bbox.max, IPoint::new(1, 4));
+        assert_eq!((bbox.width(), bbox.height()), (5, 7));
+        assert!(bbox.contains(IPoint::new(-3, 4)));
+        assert!(!bbox.contains(IPoint::new(2, 0)));
+    }
+}
@@ -0,0 +1,237 @@
+//! A rectangular grid of cells whose dimensions are only known once the input has been read, as
+//! used by the puzzles given as character maps. Cells are addressed by their column `x` and row
+//! `y` as a [`UPoint`], starting from the top left corner.
+
+use std::ops::{Index, IndexMut};
+
+use crate::{
+    geometry::{Dir, Dir8, UPoint},
+    parse::{Lines, ParseError},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    /// The cells, row by row
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Build a `width`x`height` grid from the value of each cell
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(UPoint) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| UPoint::new(x, y)))
+            .map(&mut f)
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parse the remaining lines as a map of characters, one row per line. The width is that of
+    /// the first line, and every character must be a valid cell.
+    pub fn parse(
+        mut lines: Lines,
+        cell: impl Fn(char) -> Option<T>,
+        expected: &str,
+    ) -> Result<Self, ParseError> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+        for line in lines.by_ref() {
+            let mut row = 0;
+            for (offset, c) in line.text.char_indices() {
+                if width == Some(row) {
+                    return Err(line.error_at(offset, "end of line"));
+                }
+                cells.push(cell(c).ok_or_else(|| line.error_at(offset, format!("a {expected}")))?);
+                row += 1;
+            }
+            match width {
+                None if row == 0 => return Err(line.error_at(0, format!("a {expected}"))),
+                None => width = Some(row),
+                Some(width) if row < width => {
+                    return Err(line.error_at(line.text.len(), format!("a row of {width} cells")))
+                }
+                Some(_) => (),
+            }
+            height += 1;
+        }
+        let width = width.ok_or_else(|| lines.end_error(format!("a row of cells ({expected})")))?;
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: UPoint) -> bool {
+        p.x < self.width && p.y < self.height
+    }
+
+    /// The cell at the given point, if inside the grid
+    pub fn get(&self, p: UPoint) -> Option<&T> {
+        self.contains(p)
+            .then(|| &self.cells[p.y * self.width + p.x])
+    }
+
+    pub fn get_mut(&mut self, p: UPoint) -> Option<&mut T> {
+        self.contains(p)
+            .then(|| &mut self.cells[p.y * self.width + p.x])
+    }
+
+    /// The cells of row `y`, from left to right
+    ///
+    /// Panics if the row is outside the grid.
+    pub fn row(&self, y: usize) -> &[T] {
+        assert!(y < self.height, "Row {y} outside of the grid");
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// The cells of column `x`, from top to bottom
+    ///
+    /// Panics if the column is outside the grid.
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator + '_ {
+        assert!(x < self.width, "Column {x} outside of the grid");
+        (0..self.height).map(move |y| &self.cells[y * self.width + x])
+    }
+
+    /// All the rows, from top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        // `chunks` doesn't accept a size of 0, but then there are no cells anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The coordinates of every cell, row by row
+    pub fn positions(&self) -> impl Iterator<Item = UPoint> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| UPoint::new(x, y)))
+    }
+
+    /// Every cell along with its coordinates, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (UPoint, &T)> + '_ {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The coordinates of the first cell matching the predicate, row by row
+    pub fn position(&self, pred: impl Fn(&T) -> bool) -> Option<UPoint> {
+        let i = self.cells.iter().position(pred)?;
+        Some(UPoint::new(i % self.width, i / self.width))
+    }
+
+    /// The cells above, right, below and left of the given one, inside the grid
+    pub fn neighbours4(&self, p: UPoint) -> impl Iterator<Item = UPoint> + '_ {
+        Dir::ALL
+            .into_iter()
+            .filter_map(move |dir| p.step(dir).filter(|&n| self.contains(n)))
+    }
+
+    /// The cells around the given one clockwise from above, diagonals included, inside the grid
+    pub fn neighbours8(&self, p: UPoint) -> impl Iterator<Item = UPoint> + '_ {
+        Dir8::ALL
+            .into_iter()
+            .filter_map(move |dir| p.step(dir).filter(|&n| self.contains(n)))
+    }
+
+    /// A grid of the same size, each cell being transformed by `f`
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+/// The cell at the given point. Panics if it is outside the grid.
+impl<T> Index<UPoint> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: UPoint) -> &T {
+        self.get(p)
+            .unwrap_or_else(|| panic!("{p} outside of the grid"))
+    }
+}
+
+impl<T> IndexMut<UPoint> for Grid<T> {
+    fn index_mut(&mut self, p: UPoint) -> &mut T {
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("{p} outside of the grid"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digit(c: char) -> Option<u8> {
+        c.to_digit(10).map(|d| d as u8)
+    }
+
+    fn parse(data: &str) -> Result<Grid<u8>, ParseError> {
+        Grid::parse(Lines::new(8, data), digit, "digit")
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = parse("123\n456\n").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[UPoint::new(2, 0)], 3);
+        assert_eq!(grid.get(UPoint::new(0, 1)), Some(&4));
+        assert_eq!(grid.get(UPoint::new(3, 0)), None);
+        assert_eq!(grid.get(UPoint::new(0, 2)), None);
+
+        let err = |data| {
+            let err = parse(data).unwrap_err();
+            (err.line, err.column, err.expected)
+        };
+        assert_eq!(err("12\n3x\n"), (2, 2, "a digit".to_string()));
+        assert_eq!(err("12\n3\n"), (2, 2, "a row of 2 cells".to_string()));
+        assert_eq!(err("12\n345\n"), (2, 3, "end of line".to_string()));
+        assert_eq!(err("12\n\n"), (2, 1, "a row of 2 cells".to_string()));
+        assert_eq!(err("\n12\n"), (1, 1, "a digit".to_string()));
+        assert_eq!(err(""), (1, 1, "a row of cells (digit)".to_string()));
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let mut grid = Grid::from_fn(3, 2, |p| 10 * p.y + p.x);
+        assert_eq!(grid.row(1), &[10, 11, 12]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), [1, 11]);
+        assert_eq!(grid.column(2).rev().copied().collect::<Vec<_>>(), [12, 2]);
+        assert_eq!(grid.rows().count(), 2);
+
+        grid[UPoint::new(0, 1)] = 42;
+        assert_eq!(grid.iter().nth(3), Some((UPoint::new(0, 1), &42)));
+        assert_eq!(grid.position(|&c| c > 11), Some(UPoint::new(0, 1)));
+        assert_eq!(grid.map(|c| c % 2).row(0), &[0, 1, 0]);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::from_fn(3, 3, |_| ());
+        let neighbours4 = |x, y| {
+            grid.neighbours4(UPoint::new(x, y))
+                .map(<(usize, usize)>::from)
+                .collect::<Vec<_>>()
+        };
+        let neighbours8 = |x, y| grid.neighbours8(UPoint::new(x, y)).count();
+        assert_eq!(neighbours4(1, 1), [(1, 0), (2, 1), (1, 2), (0, 1)]);
+        assert_eq!(neighbours4(0, 0), [(1, 0), (0, 1)]);
+        assert_eq!(neighbours4(2, 2), [(2, 1), (1, 2)]);
+        assert_eq!(neighbours8(1, 1), 8);
+        assert_eq!(neighbours8(0, 0), 3);
+        assert_eq!(neighbours8(2, 1), 5);
+    }
+}
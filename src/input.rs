@@ -0,0 +1,181 @@
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use eyre::{bail, Report, Result, WrapErr};
+
+/// Environment variable pointing to a directory containing the `dayNN.txt` input files
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// An input given explicitly on the command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    File(PathBuf),
+    Stdin,
+}
+
+impl FromStr for InputSource {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => bail!("Empty input path"),
+            "-" => Ok(Self::Stdin),
+            path => Ok(Self::File(PathBuf::from(path))),
+        }
+    }
+}
+
+/// The directories searched for `dayNN.txt` when no input was given explicitly
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputDirs {
+    /// Set from `AOC_INPUT_DIR`. When present, it is the only directory searched.
+    pub env_dir: Option<PathBuf>,
+    /// `inputs/` in the current directory
+    pub local_dir: PathBuf,
+    /// Per-user directory, `$XDG_DATA_HOME/aoc2022/inputs` or `~/.local/share/aoc2022/inputs`
+    pub user_dir: Option<PathBuf>,
+}
+
+impl InputDirs {
+    pub fn from_env() -> Self {
+        let env_dir = std::env::var_os(INPUT_DIR_VAR)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from);
+        let user_dir = std::env::var_os("XDG_DATA_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))
+            .map(|dir| dir.join("aoc2022").join("inputs"));
+
+        Self {
+            env_dir,
+            local_dir: PathBuf::from("inputs"),
+            user_dir,
+        }
+    }
+
+    /// The paths tried for the given day, in order
+    pub fn candidates(&self, day: u8) -> Vec<PathBuf> {
+        let file_name = file_name(day);
+        if let Some(dir) = &self.env_dir {
+            return vec![dir.join(file_name)];
+        }
+
+        let mut candidates = vec![self.local_dir.join(&file_name)];
+        candidates.extend(self.user_dir.iter().map(|dir| dir.join(&file_name)));
+        candidates
+    }
+
+    /// The directory inputs are downloaded to: `AOC_INPUT_DIR` if set, `inputs/` otherwise
+    pub fn cache_dir(&self) -> &Path {
+        self.env_dir.as_deref().unwrap_or(&self.local_dir)
+    }
+
+    /// Read the input of the given day from the first candidate path that exists
+    pub fn read(&self, day: u8) -> Result<String> {
+        let candidates = self.candidates(day);
+        match candidates.iter().find(|path| path.exists()) {
+            Some(path) => read_file(day, path),
+            None => {
+                let tried = candidates
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                bail!("No input found for day {day} (tried: {tried})");
+            }
+        }
+    }
+}
+
+/// Read the input of the given day, either from the explicit source or from the input directories
+pub fn read_input(day: u8, source: Option<&InputSource>, dirs: &InputDirs) -> Result<String> {
+    match source {
+        Some(InputSource::File(path)) => read_file(day, path),
+        Some(InputSource::Stdin) => {
+            let mut data = String::new();
+            std::io::stdin()
+                .read_to_string(&mut data)
+                .wrap_err_with(|| format!("Failed to read input for day {day} from stdin"))?;
+            Ok(data)
+        }
+        None => dirs.read(day),
+    }
+}
+
+fn read_file(day: u8, path: &Path) -> Result<String> {
+    std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("Failed to read input for day {day} from {}", path.display()))
+}
+
+pub fn file_name(day: u8) -> String {
+    format!("day{day:02}.txt")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dirs() -> InputDirs {
+        InputDirs {
+            env_dir: None,
+            local_dir: PathBuf::from("inputs"),
+            user_dir: Some(PathBuf::from("/home/elf/.local/share/aoc2022/inputs")),
+        }
+    }
+
+    #[test]
+    fn test_input_source() {
+        assert_eq!("-".parse::<InputSource>().unwrap(), InputSource::Stdin);
+        assert_eq!(
+            "foo/day07.txt".parse::<InputSource>().unwrap(),
+            InputSource::File(PathBuf::from("foo/day07.txt"))
+        );
+        assert!("".parse::<InputSource>().is_err());
+    }
+
+    #[test]
+    fn test_candidates() {
+        let mut dirs = dirs();
+        assert_eq!(
+            dirs.candidates(7),
+            vec![
+                PathBuf::from("inputs/day07.txt"),
+                PathBuf::from("/home/elf/.local/share/aoc2022/inputs/day07.txt")
+            ]
+        );
+
+        dirs.env_dir = Some(PathBuf::from("/tmp/aoc"));
+        assert_eq!(
+            dirs.candidates(12),
+            vec![PathBuf::from("/tmp/aoc/day12.txt")]
+        );
+    }
+
+    #[test]
+    fn test_missing_input() {
+        let dirs = InputDirs {
+            env_dir: Some(PathBuf::from("/nonexistent")),
+            ..dirs()
+        };
+        let err = dirs.read(3).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No input found for day 3 (tried: /nonexistent/day03.txt)"
+        );
+
+        let err = read_input(
+            3,
+            Some(&InputSource::File(PathBuf::from("/nonexistent/foo.txt"))),
+            &dirs,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to read input for day 3 from /nonexistent/foo.txt"
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//! Solutions to the [Advent of Code 2022](https://adventofcode.com/2022) puzzles.
+//!
+//! Each `dayNN` module exposes the model of that day's puzzle along with a unit struct
+//! implementing [`Solution`](solution::Solution). [`DAYS`] lists all of them so they can be run
+//! generically, see the [`runner`] module.
+
+use solution::DynSolution;
+
+pub mod answers;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod export;
+pub mod fetch;
+pub mod generate;
+pub mod geometry;
+pub mod grid;
+pub mod input;
+pub mod parse;
+#[cfg(feature = "reference")]
+pub mod reference;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+pub mod submit;
+pub mod visualize;
+pub mod watch;
+
+/// All the implemented days, in order
+pub const DAYS: [&dyn DynSolution; 13] = [
+    &day01::Day01,
+    &day02::Day02,
+    &day03::Day03,
+    &day04::Day04,
+    &day05::Day05,
+    &day06::Day06,
+    &day07::Day07,
+    &day08::Day08,
+    &day09::Day09,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+];
+
+/// Look up the solution of the given day
+pub fn find_day(day: u8) -> Option<&'static dyn DynSolution> {
+    DAYS.iter().find(|s| s.day() == day).copied()
+}
@@ -1,33 +1,346 @@
-use eyre::Result;
-
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use clap::Parser;
+use eyre::{bail, ContextCompat, Result, WrapErr};
+
+use aoc2022::{
+    answers::{self, ExpectedAnswers, Verdict},
+    export,
+    fetch::{self, Fetched},
+    generate,
+    input::{self, InputDirs, InputSource},
+    runner::{self, Outcome},
+    scaffold,
+    solution::{Answer, DynSolution, Part},
+    submit::{self, Feedback, History},
+    visualize::{self, Gif, Terminal, TextFile},
+    watch,
+};
+use cli::{Cli, Command, DaySelection, Format, Visualize};
+
+mod cli;
 
 fn main() -> Result<()> {
-    day01::run()?;
-    day02::run()?;
-    day03::run()?;
-    day04::run()?;
-    day05::run()?;
-    day06::run()?;
-    day07::run()?;
-    day08::run()?;
-    day09::run()?;
-    day10::run()?;
-    day11::run()?;
-    day12::run()?;
-    day13::run()?;
+    let cli = Cli::parse();
+
+    match cli.command {
+        Some(Command::Run {
+            days,
+            part,
+            input,
+            view,
+            ..
+        }) if view.visualize => visualize(&days, part, input.as_ref(), &view),
+        Some(Command::Run {
+            days,
+            part,
+            input,
+            format,
+            jobs,
+            ..
+        }) => run(&days, part, input.as_ref(), format, jobs),
+        Some(Command::Bench {
+            days,
+            part,
+            input,
+            iterations,
+        }) => bench(&days, part, input.as_ref(), iterations),
+        Some(Command::Verify {
+            days,
+            answers,
+            jobs,
+        }) => verify(&days, &answers, jobs),
+        Some(Command::Fetch { days, base_url }) => fetch(&days, base_url.as_deref()),
+        Some(Command::Submit {
+            day,
+            part,
+            answer,
+            base_url,
+            history,
+        }) => submit(day, part, answer, base_url.as_deref(), &history),
+        Some(Command::Export {
+            day,
+            output,
+            part,
+            input,
+            scale,
+        }) => export(day, part, input.as_ref(), scale, &output),
+        Some(Command::Watch { day }) => watch::watch(day, &InputDirs::from_env()),
+        Some(Command::New { day }) => new_day(day),
+        Some(Command::Gen {
+            day,
+            seed,
+            size,
+            width,
+            height,
+            depth,
+            rounds,
+        }) => {
+            let params = generate::Params {
+                seed,
+                size,
+                width,
+                height,
+                depth,
+                rounds,
+            };
+            print!("{}", generate::generate(day, &params)?);
+            Ok(())
+        }
+        None => run(&DaySelection::all(), None, None, Format::Text, 1),
+    }
+}
+
+fn run(
+    days: &DaySelection,
+    part: Option<Part>,
+    source: Option<&InputSource>,
+    format: Format,
+    jobs: usize,
+) -> Result<()> {
+    let dirs = InputDirs::from_env();
+    let (solutions, not_implemented) = selected_days(days, source)?;
+    let read = |day| input::read_input(day, source, &dirs);
+    let results = runner::run_days(&solutions, part, jobs, read)?;
+
+    for (solution, result) in solutions.iter().zip(&results) {
+        match (result, format) {
+            (Ok(result), Format::Text) => runner::print_answers(result),
+            (Ok(result), Format::Json) => runner::print_json(result)?,
+            (Err(e), Format::Text) => runner::print_day_error(solution.day(), e),
+            (Err(e), Format::Json) => runner::print_json_error(solution.day(), e)?,
+        }
+    }
+
+    let mut outcomes = solutions
+        .iter()
+        .zip(&results)
+        .map(|(solution, result)| (solution.day(), Outcome::Ran(result)))
+        .chain(
+            not_implemented
+                .iter()
+                .map(|day| (*day, Outcome::NotImplemented)),
+        )
+        .collect::<Vec<_>>();
+    outcomes.sort_by_key(|(day, _)| *day);
+    let failed = match format {
+        Format::Text => {
+            let solved = results.iter().flatten().collect::<Vec<_>>();
+            runner::print_timings(&solved);
+            runner::print_summary(&outcomes, part)
+        }
+        Format::Json => outcomes.iter().filter(|(_, o)| o.is_failure()).count(),
+    };
+    if failed > 0 {
+        bail!("{failed} day(s) failed");
+    }
+
+    Ok(())
+}
+
+fn visualize(
+    days: &DaySelection,
+    part: Option<Part>,
+    source: Option<&InputSource>,
+    view: &Visualize,
+) -> Result<()> {
+    let [day] = days.days() else {
+        bail!("Only a single day can be visualised at once");
+    };
+    let fps = view.fps;
+    if !fps.is_finite() || fps <= 0.0 {
+        bail!("The frame rate must be positive, not {fps}");
+    }
+    let data = input::read_input(*day, source, &InputDirs::from_env())?;
+    let part = part.unwrap_or(Part::One);
+    match (&view.frames, &view.gif) {
+        (Some(path), _) => {
+            let file = File::create(path)
+                .wrap_err_with(|| format!("Failed to create {}", path.display()))?;
+            let mut screen = TextFile::new(BufWriter::new(file));
+            visualize::visualize(*day, &data, part, &mut screen)?;
+            let count = screen.count();
+            screen.into_inner().flush()?;
+            println!("Wrote {count} frames to {}", path.display());
+        }
+        (None, Some(path)) => {
+            let mut gif = Gif::new(
+                visualize::palette(*day),
+                view.scale as usize,
+                view.skip,
+                fps,
+            );
+            visualize::visualize(*day, &data, part, &mut gif)?;
+            let file = File::create(path)
+                .wrap_err_with(|| format!("Failed to create {}", path.display()))?;
+            let count = gif.finish(BufWriter::new(file))?;
+            println!("Recorded {count} frames in {}", path.display());
+        }
+        (None, None) => visualize::visualize(*day, &data, part, &mut Terminal::new(fps))?,
+    }
+    Ok(())
+}
+
+fn bench(
+    days: &DaySelection,
+    part: Option<Part>,
+    source: Option<&InputSource>,
+    iterations: usize,
+) -> Result<()> {
+    let dirs = InputDirs::from_env();
+    let (solutions, not_implemented) = selected_days(days, source)?;
+    for day in not_implemented {
+        println!("Skipping day {day}, which is not implemented yet");
+    }
+    let mut results = Vec::new();
+    for solution in solutions {
+        let data = input::read_input(solution.day(), source, &dirs)?;
+        results.push(runner::bench_day(solution, &data, part, iterations)?);
+    }
+    runner::print_bench(&results, iterations);
+
+    Ok(())
+}
 
+fn verify(days: &DaySelection, answers: &Path, jobs: usize) -> Result<()> {
+    let expected = ExpectedAnswers::load(answers)?;
+    let dirs = InputDirs::from_env();
+    let (solutions, not_implemented) = selected_days(days, None)?;
+    let read = |day| input::read_input(day, None, &dirs);
+
+    let mut verdicts = Vec::new();
+    let mut errors = 0;
+    for (solution, result) in solutions
+        .iter()
+        .zip(runner::run_days(&solutions, None, jobs, read)?)
+    {
+        match result {
+            Ok(result) => {
+                verdicts.extend(runner::print_verification(&result, &expected));
+                errors += result.failures.len();
+            }
+            Err(e) => {
+                runner::print_day_error(solution.day(), &e);
+                errors += Part::ALL.len();
+            }
+        }
+    }
+
+    let count = |f: fn(&Verdict) -> bool| verdicts.iter().filter(|v| f(v)).count();
+    let failures = count(|v| matches!(v, Verdict::Fail { .. }));
+    println!(
+        "\n{} passed, {failures} failed, {errors} errored, {} missing, {} skipped",
+        count(|v| *v == Verdict::Pass),
+        count(|v| *v == Verdict::Missing),
+        not_implemented.len() * Part::ALL.len()
+    );
+    if failures + errors > 0 {
+        bail!("{failures} answer(s) did not match the expected ones and {errors} couldn't be computed");
+    }
+    Ok(())
+}
+
+fn fetch(days: &DaySelection, base_url: Option<&str>) -> Result<()> {
+    let dirs = InputDirs::from_env();
+    let mut client = fetch::Client::from_env(base_url)?;
+    for &day in days.days() {
+        match fetch::fetch_input(&mut client, &dirs, day)? {
+            Fetched::Downloaded(path) => println!("Day {day:02}: downloaded to {}", path.display()),
+            Fetched::Cached(path) => println!("Day {day:02}: already in {}", path.display()),
+        }
+    }
+    Ok(())
+}
+
+fn submit(
+    day: u8,
+    part: Part,
+    answer: Option<String>,
+    base_url: Option<&str>,
+    history_file: &Path,
+) -> Result<()> {
+    let answer = match answer {
+        Some(answer) => answer
+            .parse::<i64>()
+            .map_or_else(|_| Answer::Text(answer), Answer::Integer),
+        None => {
+            let solution = aoc2022::find_day(day).with_context(|| {
+                format!("Day {day} is not implemented yet, pass the answer with --answer")
+            })?;
+            let data = input::read_input(day, None, &InputDirs::from_env())?;
+            let mut result = runner::run_day(solution, &data, Some(part))?;
+            if let Some((_, e)) = result.failures.pop() {
+                return Err(e.wrap_err(format!("Failed to solve day {day} part {part}")));
+            }
+            let part = result.parts.pop().context("No answer computed")?;
+            part.answer
+        }
+    };
+
+    let mut history = History::load(history_file)?;
+    let mut client = fetch::Client::from_env(base_url)?;
+    println!("Day {day:02} part {part}: submitting {answer}");
+    let feedback = submit::submit(&mut client, &mut history, day, part, &answer);
+    // Save the verdict even if it isn't the right answer
+    history.save(history_file)?;
+    match feedback? {
+        Feedback::Correct => println!("That's the right answer!"),
+        feedback => bail!("{answer} is not the right answer: it is {feedback}"),
+    }
     Ok(())
 }
+
+fn export(
+    day: u8,
+    part: Part,
+    source: Option<&InputSource>,
+    scale: u64,
+    output: &Path,
+) -> Result<()> {
+    let data = input::read_input(day, source, &InputDirs::from_env())?;
+    export::export_day(day, &data, part, scale as usize, output)?;
+    println!("Saved day {day} part {part} to {}", output.display());
+    Ok(())
+}
+
+fn new_day(day: u8) -> Result<()> {
+    for path in scaffold::new_day(Path::new("."), day)? {
+        println!("Wrote {}", path.display());
+    }
+    println!(
+        "Add the answers of the example to {} to test it",
+        answers::EXAMPLE_ANSWERS_FILE
+    );
+    Ok(())
+}
+
+/// Look up the solutions of the selected days, along with the selected days that are not
+/// implemented yet. When running all days, those after the last implemented one are ignored.
+fn selected_days(
+    days: &DaySelection,
+    source: Option<&InputSource>,
+) -> Result<(Vec<&'static dyn DynSolution>, Vec<u8>)> {
+    if source.is_some() && days.days().len() > 1 {
+        bail!("An explicit input can only be used when running a single day");
+    }
+
+    let mut solutions = Vec::new();
+    let mut not_implemented = Vec::new();
+    for &day in days.days() {
+        match aoc2022::find_day(day) {
+            Some(solution) => solutions.push(solution),
+            None if days.is_all() => break,
+            None => not_implemented.push(day),
+        }
+    }
+    if let [day] = days.days() {
+        if solutions.is_empty() {
+            bail!("Day {day} is not implemented yet");
+        }
+    }
+    Ok((solutions, not_implemented))
+}
@@ -0,0 +1,274 @@
+//! Helpers shared by the parsers of all days, reporting where and why an input is invalid
+
+use std::{fmt::Display, str::FromStr};
+
+/// An invalid input: where the problem is and what was expected there
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    /// Line number, starting at 1
+    pub line: usize,
+    /// Column of the offending character, starting at 1
+    pub column: usize,
+    /// The text of the offending line
+    pub text: String,
+    /// What was expected at that position
+    pub expected: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Day {}, line {}, column {}: expected {}",
+            self.day, self.line, self.column, self.expected
+        )?;
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        writeln!(f, "{number} | {}", self.text)?;
+        write!(f, "{gutter} | {}^", " ".repeat(self.column - 1))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A line of a day's input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    pub day: u8,
+    /// Line number, starting at 1
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// An error at the given byte offset of the line
+    pub fn error_at(&self, offset: usize, expected: impl Into<String>) -> ParseError {
+        let offset = offset.min(self.text.len());
+        let column = self
+            .text
+            .get(..offset)
+            .map_or(offset, |s| s.chars().count())
+            + 1;
+        ParseError {
+            day: self.day,
+            line: self.number,
+            column,
+            text: self.text.to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    /// A scanner reading the line from its start
+    pub fn scanner(&self) -> Scanner<'a> {
+        Scanner {
+            line: *self,
+            pos: 0,
+        }
+    }
+}
+
+/// The lines of a day's input, keeping track of their numbers
+#[derive(Debug, Clone)]
+pub struct Lines<'a> {
+    day: u8,
+    lines: std::str::Lines<'a>,
+    number: usize,
+}
+
+impl<'a> Lines<'a> {
+    pub fn new(day: u8, data: &'a str) -> Self {
+        Self {
+            day,
+            lines: data.lines(),
+            number: 0,
+        }
+    }
+
+    /// The next line, or an error if the input ended before it
+    pub fn expect_line(&mut self, expected: &str) -> Result<Line<'a>, ParseError> {
+        self.next().ok_or_else(|| self.end_error(expected))
+    }
+
+    /// An error at the end of the input
+    pub fn end_error(&self, expected: impl Into<String>) -> ParseError {
+        Line {
+            day: self.day,
+            number: self.number + 1,
+            text: "",
+        }
+        .error_at(0, expected)
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Line<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let text = self.lines.next()?;
+        self.number += 1;
+        Some(Line {
+            day: self.day,
+            number: self.number,
+            text,
+        })
+    }
+}
+
+/// Reads a line from left to right, reporting errors at the current position
+#[derive(Debug, Clone)]
+pub struct Scanner<'a> {
+    line: Line<'a>,
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    /// Byte offset of the next character to read
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// The part of the line that hasn't been read yet
+    pub fn rest(&self) -> &'a str {
+        &self.line.text[self.pos..]
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.pos == self.line.text.len()
+    }
+
+    /// An error at the current position
+    pub fn error(&self, expected: impl Into<String>) -> ParseError {
+        self.line.error_at(self.pos, expected)
+    }
+
+    /// Read the given text
+    pub fn tag(&mut self, tag: &str) -> Result<(), ParseError> {
+        if self.rest().starts_with(tag) {
+            self.pos += tag.len();
+            Ok(())
+        } else {
+            Err(self.error(format!("{tag:?}")))
+        }
+    }
+
+    /// Read the given text if it comes next, returning whether it did
+    pub fn try_tag(&mut self, tag: &str) -> bool {
+        self.tag(tag).is_ok()
+    }
+
+    /// Read a single character, which must be one of the given ones
+    pub fn one_of(&mut self, chars: &str) -> Result<char, ParseError> {
+        match self.rest().chars().next() {
+            Some(c) if chars.contains(c) => {
+                self.pos += c.len_utf8();
+                Ok(c)
+            }
+            _ => {
+                let chars = chars.chars().map(|c| format!("{c:?}")).collect::<Vec<_>>();
+                Err(self.error(format!("one of {}", chars.join(", "))))
+            }
+        }
+    }
+
+    /// Read characters as long as they match the predicate
+    pub fn take_while(&mut self, pred: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(|c| !pred(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// Read everything up to the end of the line
+    pub fn take_rest(&mut self) -> &'a str {
+        let rest = self.rest();
+        self.pos = self.line.text.len();
+        rest
+    }
+
+    /// Read characters as long as they match the predicate and parse them. On failure, the error
+    /// points to the start of the token.
+    pub fn parse<T: FromStr>(
+        &mut self,
+        pred: impl Fn(char) -> bool,
+        expected: &str,
+    ) -> Result<T, ParseError> {
+        let start = self.pos;
+        self.take_while(pred)
+            .parse::<T>()
+            .map_err(|_| self.line.error_at(start, expected))
+    }
+
+    /// Read a (possibly negative) integer
+    pub fn number<T: FromStr>(&mut self, expected: &str) -> Result<T, ParseError> {
+        let start = self.pos;
+        self.try_tag("-");
+        let digits = self.take_while(|c| c.is_ascii_digit()).len();
+        self.line.text[start..self.pos]
+            .parse::<T>()
+            .ok()
+            .filter(|_| digits > 0)
+            .ok_or_else(|| self.line.error_at(start, expected))
+    }
+
+    /// Check that the whole line has been read
+    pub fn end(&self) -> Result<(), ParseError> {
+        if self.is_at_end() {
+            Ok(())
+        } else {
+            Err(self.error("end of line"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let mut lines = Lines::new(5, "    [D]\n\nmove 1 from x to 2");
+        let line = lines.nth(2).unwrap();
+        let mut s = line.scanner();
+        s.tag("move ").unwrap();
+        let _: usize = s.number("a number of crates").unwrap();
+        s.tag(" from ").unwrap();
+        let err = s.number::<usize>("a stack number").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Day 5, line 3, column 13: expected a stack number\n\
+             3 | move 1 from x to 2\n  \
+               |             ^"
+        );
+    }
+
+    #[test]
+    fn test_scanner() {
+        let line = Line {
+            day: 4,
+            number: 1,
+            text: "2-4,-6",
+        };
+        let mut s = line.scanner();
+        assert_eq!(s.number::<u64>("a number"), Ok(2));
+        assert_eq!(s.tag("-"), Ok(()));
+        assert_eq!(s.number::<u64>("a number"), Ok(4));
+        assert!(!s.try_tag("-"));
+        assert_eq!(s.tag("-").unwrap_err().column, 4);
+        s.tag(",").unwrap();
+        assert_eq!(s.number::<i64>("a number"), Ok(-6));
+        assert_eq!(s.end(), Ok(()));
+
+        let mut s = line.scanner();
+        s.tag("2-4,").unwrap();
+        assert_eq!(s.number::<u64>("a section").unwrap_err().column, 5);
+    }
+
+    #[test]
+    fn test_end_of_input() {
+        let mut lines = Lines::new(13, "[1]\n");
+        lines.next();
+        let err = lines.expect_line("a second packet").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+    }
+}
@@ -0,0 +1,667 @@
+//! Deliberately naive solutions of every day, following the puzzle statements as literally as
+//! possible. They share nothing with the real solutions, parsing included, so that comparing
+//! both on the same inputs can reveal subtle bugs in the shortcuts the real ones take. They
+//! assume valid inputs, and are only built with the `reference` feature.
+
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, VecDeque},
+};
+
+use eyre::{bail, eyre, ContextCompat, Result};
+
+use crate::solution::{Answer, Part};
+
+/// The days that have a reference solution
+pub const DAYS: [u8; 13] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13];
+
+/// Solve a part of a day the naive way
+pub fn solve(day: u8, data: &str, part: Part) -> Result<Answer> {
+    match day {
+        1 => day01(data, part),
+        2 => day02(data, part),
+        3 => day03(data, part),
+        4 => day04(data, part),
+        5 => day05(data, part),
+        6 => day06(data, part),
+        7 => day07(data, part),
+        8 => day08(data, part),
+        9 => day09(data, part),
+        10 => day10(data, part),
+        11 => day11(data, part),
+        12 => day12(data, part),
+        13 => day13(data, part),
+        _ => bail!("No reference solution for day {day}"),
+    }
+}
+
+/// Sum of the calories carried by the elf carrying the most (part 1) or by the 3 of them (part 2)
+fn day01(data: &str, part: Part) -> Result<Answer> {
+    let mut totals = Vec::new();
+    for elf in data.split("\n\n") {
+        let mut total = 0u64;
+        for line in elf.lines() {
+            total += line.trim().parse::<u64>()?;
+        }
+        totals.push(total);
+    }
+
+    let top = match part {
+        Part::One => 1,
+        Part::Two => 3,
+    };
+    let mut sum = 0;
+    for _ in 0..top {
+        let Some(max) = totals.iter().copied().max() else {
+            break;
+        };
+        sum += max;
+        let i = totals
+            .iter()
+            .position(|t| *t == max)
+            .expect("max is in totals");
+        totals.remove(i);
+    }
+    sum.try_into()
+}
+
+/// Total score of the rock paper scissors strategy guide
+fn day02(data: &str, part: Part) -> Result<Answer> {
+    // 0 for rock, 1 for paper, 2 for scissors
+    let beats = |a: u64, b: u64| [(0, 2), (1, 0), (2, 1)].contains(&(a, b));
+    let outcome_score = |ours: u64, theirs: u64| {
+        if beats(ours, theirs) {
+            6
+        } else if ours == theirs {
+            3
+        } else {
+            0
+        }
+    };
+
+    let mut total = 0;
+    for line in data.lines() {
+        let bytes = line.as_bytes();
+        let theirs = u64::from(bytes[0] - b'A');
+        let second = u64::from(bytes[2] - b'X');
+        let ours = match part {
+            Part::One => second,
+            // Try every shape until one gives the expected outcome (lose, draw or win)
+            Part::Two => (0..3)
+                .find(|ours| outcome_score(*ours, theirs) == second * 3)
+                .expect("one shape per outcome"),
+        };
+        total += ours + 1 + outcome_score(ours, theirs);
+    }
+    total.try_into()
+}
+
+fn priority(item: char) -> u64 {
+    if item.is_ascii_lowercase() {
+        item as u64 - 'a' as u64 + 1
+    } else {
+        item as u64 - 'A' as u64 + 27
+    }
+}
+
+/// Sum of the priorities of the items in both compartments (part 1) or in the 3 rucksacks of a
+/// group (part 2)
+fn day03(data: &str, part: Part) -> Result<Answer> {
+    let lines = data.lines().collect::<Vec<_>>();
+    let mut total = 0;
+    match part {
+        Part::One => {
+            for line in lines {
+                let (left, right) = line.split_at(line.len() / 2);
+                let common = left
+                    .chars()
+                    .find(|c| right.contains(*c))
+                    .with_context(|| format!("No common item in {line}"))?;
+                total += priority(common);
+            }
+        }
+        Part::Two => {
+            for group in lines.chunks(3) {
+                let [a, b, c] = group else {
+                    bail!("Incomplete group of rucksacks");
+                };
+                let badge = a
+                    .chars()
+                    .find(|item| b.contains(*item) && c.contains(*item))
+                    .context("No badge")?;
+                total += priority(badge);
+            }
+        }
+    }
+    total.try_into()
+}
+
+/// Number of pairs where a range contains the other (part 1) or where they overlap (part 2)
+fn day04(data: &str, part: Part) -> Result<Answer> {
+    let sections = |range: &str| -> Result<Vec<u64>> {
+        let (start, end) = range.split_once('-').context("No '-'")?;
+        Ok((start.parse()?..=end.parse()?).collect())
+    };
+
+    let mut count = 0u64;
+    for line in data.lines() {
+        let (a, b) = line.split_once(',').context("No ','")?;
+        let (a, b) = (sections(a)?, sections(b)?);
+        let counted = match part {
+            Part::One => a.iter().all(|s| b.contains(s)) || b.iter().all(|s| a.contains(s)),
+            Part::Two => a.iter().any(|s| b.contains(s)),
+        };
+        if counted {
+            count += 1;
+        }
+    }
+    count.try_into()
+}
+
+/// The crates on top of each stack after the moves, made one crate at a time (part 1) or all at
+/// once (part 2)
+fn day05(data: &str, part: Part) -> Result<Answer> {
+    let (drawing, moves) = data.split_once("\n\n").context("No empty line")?;
+    let mut rows = drawing.lines().collect::<Vec<_>>();
+    let numbers = rows.pop().context("No stack numbers")?;
+    let num_stacks = numbers.split_whitespace().count();
+
+    let mut stacks = vec![Vec::new(); num_stacks];
+    for row in rows.iter().rev() {
+        let row = row.chars().collect::<Vec<_>>();
+        for (i, stack) in stacks.iter_mut().enumerate() {
+            match row.get(4 * i + 1) {
+                Some(c) if c.is_ascii_uppercase() => stack.push(*c),
+                _ => (),
+            }
+        }
+    }
+
+    for line in moves.lines() {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        let num = words[1].parse::<usize>()?;
+        let from = words[3].parse::<usize>()? - 1;
+        let to = words[5].parse::<usize>()? - 1;
+        match part {
+            Part::One => {
+                for _ in 0..num {
+                    let c = stacks[from].pop().context("Empty stack")?;
+                    stacks[to].push(c);
+                }
+            }
+            Part::Two => {
+                let at = stacks[from]
+                    .len()
+                    .checked_sub(num)
+                    .context("Not enough crates")?;
+                let crates = stacks[from].split_off(at);
+                stacks[to].extend(crates);
+            }
+        }
+    }
+
+    let top = stacks
+        .iter()
+        .map(|stack| stack.last().copied().unwrap_or(' '))
+        .collect::<String>();
+    Ok(top.into())
+}
+
+/// Number of characters read until the last 4 (part 1) or 14 (part 2) are all different
+fn day06(data: &str, part: Part) -> Result<Answer> {
+    let size = match part {
+        Part::One => 4,
+        Part::Two => 14,
+    };
+    let chars = data.trim().chars().collect::<Vec<_>>();
+    for end in size..=chars.len() {
+        let window = &chars[end - size..end];
+        let mut distinct = true;
+        for i in 0..size {
+            for j in i + 1..size {
+                if window[i] == window[j] {
+                    distinct = false;
+                }
+            }
+        }
+        if distinct {
+            return end.try_into();
+        }
+    }
+    bail!("No marker")
+}
+
+/// Sum of the sizes of the directories of at most 100000 (part 1), or size of the smallest
+/// directory to delete to free enough space (part 2)
+fn day07(data: &str, part: Part) -> Result<Answer> {
+    let mut cwd = Vec::new();
+    let mut dirs = vec![Vec::new()];
+    let mut files = Vec::new();
+    for line in data.lines() {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        match words[..] {
+            ["$", "cd", "/"] => cwd.clear(),
+            ["$", "cd", ".."] => {
+                cwd.pop();
+            }
+            ["$", "cd", name] => cwd.push(name.to_string()),
+            ["$", "ls"] => (),
+            ["dir", name] => {
+                let mut path = cwd.clone();
+                path.push(name.to_string());
+                dirs.push(path);
+            }
+            [size, name] => {
+                let mut path = cwd.clone();
+                path.push(name.to_string());
+                files.push((path, size.parse::<u64>()?));
+            }
+            _ => bail!("Invalid line {line}"),
+        }
+    }
+
+    // A directory contains all the files whose path starts with its own
+    let sizes = dirs
+        .iter()
+        .map(|dir| {
+            files
+                .iter()
+                .filter(|(path, _)| path.starts_with(dir))
+                .map(|(_, size)| size)
+                .sum::<u64>()
+        })
+        .collect::<Vec<_>>();
+    match part {
+        Part::One => sizes
+            .iter()
+            .filter(|s| **s <= 100000)
+            .sum::<u64>()
+            .try_into(),
+        Part::Two => {
+            let unused = 70_000_000u64
+                .checked_sub(sizes[0])
+                .context("Disk too full")?;
+            let needed = 30_000_000u64
+                .checked_sub(unused)
+                .context("Enough free space")?;
+            let smallest = sizes
+                .iter()
+                .filter(|s| **s >= needed)
+                .min()
+                .context("No directory big enough")?;
+            (*smallest).try_into()
+        }
+    }
+}
+
+/// Number of trees visible from outside the grid (part 1), or highest scenic score (part 2)
+fn day08(data: &str, part: Part) -> Result<Answer> {
+    let grid = data
+        .lines()
+        .map(|line| line.bytes().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let height = grid.len() as isize;
+    let width = grid.first().map_or(0, Vec::len) as isize;
+    let directions = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+
+    // The trees seen from (x, y) in the given direction, from the closest one
+    let line_of_sight = |x: isize, y: isize, (dx, dy): (isize, isize)| {
+        let mut trees = Vec::new();
+        let (mut x, mut y) = (x + dx, y + dy);
+        while (0..width).contains(&x) && (0..height).contains(&y) {
+            trees.push(grid[y as usize][x as usize]);
+            x += dx;
+            y += dy;
+        }
+        trees
+    };
+
+    let mut visible = 0u64;
+    let mut best_score = 0u64;
+    for y in 0..height {
+        for x in 0..width {
+            let tree = grid[y as usize][x as usize];
+            let mut is_visible = false;
+            let mut score = 1;
+            for dir in directions {
+                let trees = line_of_sight(x, y, dir);
+                if trees.iter().all(|t| *t < tree) {
+                    is_visible = true;
+                }
+                let seen = match trees.iter().position(|t| *t >= tree) {
+                    Some(i) => i + 1,
+                    None => trees.len(),
+                };
+                score *= seen as u64;
+            }
+            if is_visible {
+                visible += 1;
+            }
+            best_score = best_score.max(score);
+        }
+    }
+    match part {
+        Part::One => visible.try_into(),
+        Part::Two => best_score.try_into(),
+    }
+}
+
+/// Number of positions visited by the tail of a rope of 2 (part 1) or 10 (part 2) knots
+fn day09(data: &str, part: Part) -> Result<Answer> {
+    let num_knots = match part {
+        Part::One => 2,
+        Part::Two => 10,
+    };
+    let mut knots = vec![(0i64, 0i64); num_knots];
+    let mut visited = BTreeSet::from([(0, 0)]);
+    for line in data.lines() {
+        let (dir, steps) = line.split_once(' ').context("No space")?;
+        let (dx, dy) = match dir {
+            "U" => (0, 1),
+            "D" => (0, -1),
+            "L" => (-1, 0),
+            "R" => (1, 0),
+            _ => bail!("Invalid direction {dir}"),
+        };
+        for _ in 0..steps.parse::<u64>()? {
+            knots[0].0 += dx;
+            knots[0].1 += dy;
+            for i in 1..num_knots {
+                let (hx, hy) = knots[i - 1];
+                let (tx, ty) = &mut knots[i];
+                if (hx - *tx).abs() > 1 || (hy - *ty).abs() > 1 {
+                    *tx += (hx - *tx).signum();
+                    *ty += (hy - *ty).signum();
+                }
+            }
+            visited.insert(knots[num_knots - 1]);
+        }
+    }
+    visited.len().try_into()
+}
+
+/// Sum of the signal strengths at cycles 20, 60... 220 (part 1), or the image drawn on the CRT
+/// (part 2)
+fn day10(data: &str, part: Part) -> Result<Answer> {
+    // The value of X during each cycle
+    let mut xs = Vec::new();
+    let mut x = 1i64;
+    for line in data.lines() {
+        if line == "noop" {
+            xs.push(x);
+        } else {
+            let v = line.strip_prefix("addx ").context("Invalid instruction")?;
+            xs.push(x);
+            xs.push(x);
+            x += v.parse::<i64>()?;
+        }
+    }
+
+    match part {
+        Part::One => {
+            let mut sum = 0;
+            for cycle in [20, 60, 100, 140, 180, 220] {
+                if let Some(x) = xs.get(cycle - 1) {
+                    sum += cycle as i64 * x;
+                }
+            }
+            Ok(sum.into())
+        }
+        Part::Two => {
+            let mut rows = Vec::new();
+            for row in 0..6 {
+                let mut pixels = String::new();
+                for col in 0..40 {
+                    let lit = xs
+                        .get(row * 40 + col)
+                        .is_some_and(|x| (x - col as i64).abs() <= 1);
+                    pixels.push(if lit { '#' } else { '.' });
+                }
+                rows.push(pixels);
+            }
+            Ok(rows.join("\n").into())
+        }
+    }
+}
+
+struct Monkey {
+    items: VecDeque<u64>,
+    /// Whether the operation is a multiplication, and its right operand (`None` for `old`)
+    operation: (bool, Option<u64>),
+    divisor: u64,
+    targets: [usize; 2],
+    inspections: u64,
+}
+
+impl Monkey {
+    fn apply(&self, old: u64) -> Option<u64> {
+        let rhs = self.operation.1.unwrap_or(old);
+        match self.operation.0 {
+            true => old.checked_mul(rhs),
+            false => old.checked_add(rhs),
+        }
+    }
+}
+
+/// Product of the number of inspections of the 2 most active monkeys, after 20 rounds with
+/// relief (part 1) or 10000 rounds without (part 2)
+fn day11(data: &str, part: Part) -> Result<Answer> {
+    let last_number = |line: &str| -> Result<u64> {
+        let word = line.split_whitespace().last().context("Empty line")?;
+        Ok(word.parse()?)
+    };
+    let mut monkeys = Vec::new();
+    for block in data.split("\n\n") {
+        let lines = block.lines().collect::<Vec<_>>();
+        let (_, items) = lines[1].split_once(": ").context("No items")?;
+        let (_, operation) = lines[2].split_once("old ").context("No operation")?;
+        let (op, rhs) = operation.split_once(' ').context("No operand")?;
+        monkeys.push(Monkey {
+            items: items
+                .split(", ")
+                .filter(|item| !item.is_empty())
+                .map(|item| item.parse())
+                .collect::<Result<_, _>>()?,
+            operation: (op == "*", rhs.parse().ok()),
+            divisor: last_number(lines[3])?,
+            targets: [
+                last_number(lines[4])? as usize,
+                last_number(lines[5])? as usize,
+            ],
+            inspections: 0,
+        });
+    }
+
+    match part {
+        Part::One => {
+            for _ in 0..20 {
+                for i in 0..monkeys.len() {
+                    while let Some(item) = monkeys[i].items.pop_front() {
+                        monkeys[i].inspections += 1;
+                        let new = monkeys[i].apply(item).context("Overflow")? / 3;
+                        let target = monkeys[i].targets[usize::from(new % monkeys[i].divisor != 0)];
+                        monkeys[target].items.push_back(new);
+                    }
+                }
+            }
+        }
+        Part::Two => {
+            // Without relief, each item is tracked by its worry level modulo each divisor
+            let divisors = monkeys.iter().map(|m| m.divisor).collect::<Vec<_>>();
+            let mut items = monkeys
+                .iter()
+                .map(|m| {
+                    m.items
+                        .iter()
+                        .map(|item| divisors.iter().map(|d| item % d).collect::<Vec<_>>())
+                        .collect::<VecDeque<_>>()
+                })
+                .collect::<Vec<_>>();
+            for _ in 0..10000 {
+                for i in 0..monkeys.len() {
+                    while let Some(mut residues) = items[i].pop_front() {
+                        monkeys[i].inspections += 1;
+                        for (residue, d) in residues.iter_mut().zip(&divisors) {
+                            *residue = monkeys[i].apply(*residue).context("Overflow")? % d;
+                        }
+                        let target = monkeys[i].targets[usize::from(residues[i] != 0)];
+                        items[target].push_back(residues);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut inspections = monkeys.iter().map(|m| m.inspections).collect::<Vec<_>>();
+    inspections.sort();
+    inspections.iter().rev().take(2).product::<u64>().try_into()
+}
+
+/// Fewest steps from the start (part 1), or from any square of elevation a (part 2), to the goal
+fn day12(data: &str, part: Part) -> Result<Answer> {
+    let grid = data
+        .lines()
+        .map(|line| line.bytes().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let elevation = |c: u8| match c {
+        b'S' => b'a',
+        b'E' => b'z',
+        c => c,
+    };
+    let cells = (0..grid.len())
+        .flat_map(|y| (0..grid[y].len()).map(move |x| (x, y)))
+        .collect::<Vec<_>>();
+
+    // Plain breadth-first search
+    let steps_from = |start: (usize, usize)| -> Option<usize> {
+        let mut seen = BTreeSet::from([start]);
+        let mut queue = VecDeque::from([(start, 0)]);
+        while let Some(((x, y), steps)) = queue.pop_front() {
+            if grid[y][x] == b'E' {
+                return Some(steps);
+            }
+            let neighbours = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            for (nx, ny) in neighbours {
+                let Some(&c) = grid.get(ny).and_then(|row| row.get(nx)) else {
+                    continue;
+                };
+                if elevation(c) <= elevation(grid[y][x]) + 1 && seen.insert((nx, ny)) {
+                    queue.push_back(((nx, ny), steps + 1));
+                }
+            }
+        }
+        None
+    };
+
+    let starts = cells.iter().filter(|(x, y)| match part {
+        Part::One => grid[*y][*x] == b'S',
+        Part::Two => elevation(grid[*y][*x]) == b'a',
+    });
+    let fewest = starts
+        .filter_map(|start| steps_from(*start))
+        .min()
+        .ok_or_else(|| eyre!("No path to the goal"))?;
+    fewest.try_into()
+}
+
+enum Packet {
+    Integer(u64),
+    List(Vec<Packet>),
+}
+
+fn parse_packet(chars: &[u8], pos: &mut usize) -> Packet {
+    if chars[*pos] == b'[' {
+        *pos += 1;
+        let mut values = Vec::new();
+        while chars[*pos] != b']' {
+            if chars[*pos] == b',' {
+                *pos += 1;
+            }
+            values.push(parse_packet(chars, pos));
+        }
+        *pos += 1;
+        Packet::List(values)
+    } else {
+        let mut v = 0;
+        while chars[*pos].is_ascii_digit() {
+            v = v * 10 + u64::from(chars[*pos] - b'0');
+            *pos += 1;
+        }
+        Packet::Integer(v)
+    }
+}
+
+/// Compare two packets as described by the puzzle
+fn compare(left: &Packet, right: &Packet) -> Ordering {
+    match (left, right) {
+        (Packet::Integer(l), Packet::Integer(r)) => l.cmp(r),
+        (Packet::Integer(l), Packet::List(_)) => {
+            compare(&Packet::List(vec![Packet::Integer(*l)]), right)
+        }
+        (Packet::List(_), Packet::Integer(r)) => {
+            compare(left, &Packet::List(vec![Packet::Integer(*r)]))
+        }
+        (Packet::List(l), Packet::List(r)) => {
+            let mut i = 0;
+            loop {
+                match (l.get(i), r.get(i)) {
+                    (None, None) => return Ordering::Equal,
+                    (None, Some(_)) => return Ordering::Less,
+                    (Some(_), None) => return Ordering::Greater,
+                    (Some(a), Some(b)) => match compare(a, b) {
+                        Ordering::Equal => i += 1,
+                        ordering => return ordering,
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// Sum of the indices of the pairs in the right order (part 1), or product of the indices of the
+/// divider packets once all the packets are sorted (part 2)
+fn day13(data: &str, part: Part) -> Result<Answer> {
+    let packets = data
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| parse_packet(line.as_bytes(), &mut 0))
+        .collect::<Vec<_>>();
+
+    match part {
+        Part::One => {
+            let mut sum = 0;
+            for (i, pair) in packets.chunks(2).enumerate() {
+                if compare(&pair[0], &pair[1]) != Ordering::Greater {
+                    sum += i + 1;
+                }
+            }
+            sum.try_into()
+        }
+        Part::Two => {
+            let divider = |v| Packet::List(vec![Packet::List(vec![Packet::Integer(v)])]);
+            // The dividers come first so that they stay before the packets equal to them
+            let mut all = vec![(true, divider(2)), (true, divider(6))];
+            all.extend(packets.into_iter().map(|p| (false, p)));
+            // Insertion sort, which is stable
+            for i in 1..all.len() {
+                let mut j = i;
+                while j > 0 && compare(&all[j - 1].1, &all[j].1) == Ordering::Greater {
+                    all.swap(j - 1, j);
+                    j -= 1;
+                }
+            }
+            let key = all
+                .iter()
+                .enumerate()
+                .filter(|(_, (is_divider, _))| *is_divider)
+                .map(|(i, _)| i + 1)
+                .product::<usize>();
+            key.try_into()
+        }
+    }
+}
@@ -0,0 +1,599 @@
+use std::{
+    panic::AssertUnwindSafe,
+    time::{Duration, Instant},
+};
+
+use eyre::{eyre, Report, Result};
+use itertools::Itertools;
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    answers::{ExpectedAnswers, Verdict},
+    solution::{Answer, DynSolution, Part},
+};
+
+/// The answers of a day along with the time it took to compute them
+#[derive(Debug)]
+pub struct DayResult {
+    pub day: u8,
+    pub parse_time: Duration,
+    /// The parts that were solved
+    pub parts: Vec<PartResult>,
+    /// The parts that failed, along with the reason why
+    pub failures: Vec<(Part, Report)>,
+}
+
+impl DayResult {
+    pub fn part(&self, part: Part) -> Option<&PartResult> {
+        self.parts.iter().find(|p| p.part == part)
+    }
+
+    /// The answer or the error of each part that was run, in order
+    pub fn outcomes(&self) -> impl Iterator<Item = (Part, Result<&PartResult, &Report>)> + '_ {
+        Part::ALL
+            .into_iter()
+            .filter_map(|part| match self.part(part) {
+                Some(p) => Some((part, Ok(p))),
+                None => self
+                    .failures
+                    .iter()
+                    .find(|(p, _)| *p == part)
+                    .map(|(_, e)| (part, Err(e))),
+            })
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.parse_time + self.parts.iter().map(|p| p.time).sum::<Duration>()
+    }
+}
+
+#[derive(Debug)]
+pub struct PartResult {
+    pub part: Part,
+    pub answer: Answer,
+    pub time: Duration,
+}
+
+/// Parse the input and solve the selected parts (or both) of a day, timing each step.
+///
+/// When both parts are selected they are solved concurrently if the current thread pool allows it.
+/// A part failing, even by panicking, doesn't prevent the other one from being solved. If the
+/// input can't be parsed, neither part is.
+pub fn run_day(solution: &dyn DynSolution, data: &str, part: Option<Part>) -> Result<DayResult> {
+    let (parsed, parse_time) = timed(|| catch_panic(|| solution.parse(data)));
+    let parsed = parsed?;
+
+    let solve = |p| {
+        let (answer, time) = timed(|| catch_panic(|| parsed.solve(p)));
+        answer
+            .map(|answer| PartResult {
+                part: p,
+                answer,
+                time,
+            })
+            .map_err(|e| (p, e))
+    };
+    let results = match part {
+        Some(p) => vec![solve(p)],
+        None => {
+            let (part1, part2) = rayon::join(|| solve(Part::One), || solve(Part::Two));
+            vec![part1, part2]
+        }
+    };
+    let (parts, failures) = results.into_iter().partition_result();
+
+    Ok(DayResult {
+        day: solution.day(),
+        parse_time,
+        parts,
+        failures,
+    })
+}
+
+/// Call `f`, turning a panic into an error
+pub fn catch_panic<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    std::panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown cause".to_string());
+        Err(eyre!("Panicked: {message}"))
+    })
+}
+
+/// Run the given days on a pool of `jobs` threads (or as many as there are CPUs if `jobs` is 0),
+/// `read` being called to get the input of each day. The results are in the same order as the days.
+pub fn run_days<F>(
+    solutions: &[&dyn DynSolution],
+    part: Option<Part>,
+    jobs: usize,
+    read: F,
+) -> Result<Vec<Result<DayResult>>>
+where
+    F: Fn(u8) -> Result<String> + Sync,
+{
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(jobs).build()?;
+    let results = pool.install(|| {
+        solutions
+            .par_iter()
+            .map(|solution| {
+                let data = catch_panic(|| read(solution.day()))?;
+                run_day(*solution, &data, part)
+            })
+            .collect()
+    });
+    Ok(results)
+}
+
+/// Statistics over the repeated runs of a single step
+#[derive(Debug, Clone, Copy)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Compute the stats of a non-empty list of samples
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        Self {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct BenchResult {
+    pub day: u8,
+    pub parse: Stats,
+    pub parts: Vec<(Part, Stats)>,
+}
+
+/// Run the parsing and the selected parts of a day `iterations` times each
+pub fn bench_day(
+    solution: &dyn DynSolution,
+    data: &str,
+    part: Option<Part>,
+    iterations: usize,
+) -> Result<BenchResult> {
+    let iterations = iterations.max(1);
+
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let (parsed, time) = timed(|| solution.parse(data));
+        parsed?;
+        samples.push(time);
+    }
+    let parse = Stats::from_samples(samples);
+
+    let parsed = solution.parse(data)?;
+    let mut parts = Vec::new();
+    for p in selected_parts(part) {
+        let mut samples = Vec::with_capacity(iterations);
+        for _ in 0..iterations {
+            let (answer, time) = timed(|| parsed.solve(p));
+            answer?;
+            samples.push(time);
+        }
+        parts.push((p, Stats::from_samples(samples)));
+    }
+
+    Ok(BenchResult {
+        day: solution.day(),
+        parse,
+        parts,
+    })
+}
+
+fn selected_parts(part: Option<Part>) -> impl Iterator<Item = Part> {
+    Part::ALL
+        .into_iter()
+        .filter(move |p| part.is_none_or(|part| part == *p))
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let res = f();
+    (res, start.elapsed())
+}
+
+/// Print the answers of a day, and the errors of the parts that failed
+pub fn print_answers(result: &DayResult) {
+    println!("Day {:02}", result.day);
+    for (part, outcome) in result.outcomes() {
+        match outcome {
+            Ok(p) => {
+                let answer = p.answer.to_string();
+                if answer.contains('\n') {
+                    println!("  Part {part}:\n{answer}");
+                } else {
+                    println!("  Part {part}: {answer}");
+                }
+            }
+            Err(error) => println!("  Part {part}: {}", indented_error(error)),
+        }
+    }
+}
+
+/// Print the error that prevented solving a day
+pub fn print_day_error(day: u8, error: &Report) {
+    println!("Day {day:02}");
+    println!("  {}", indented_error(error));
+}
+
+/// Render an error along with its causes, indenting all its lines but the first one
+fn indented_error(error: &Report) -> String {
+    format!("Error: {error:#}").replace('\n', "\n  ")
+}
+
+/// A line of the JSON output: the result of one part of a day, or the error that prevented
+/// computing it
+#[derive(Debug, Serialize)]
+pub struct JsonRecord<'a> {
+    pub day: u8,
+    pub part: Option<u8>,
+    pub answer: Option<&'a Answer>,
+    #[serde(rename = "type")]
+    pub kind: Option<&'static str>,
+    pub parse_time_ns: Option<u128>,
+    pub time_ns: Option<u128>,
+    pub error: Option<String>,
+}
+
+/// Print the answers of a day as JSON, one object per line and per part, including the parts that
+/// failed
+pub fn print_json(result: &DayResult) -> Result<()> {
+    for (part, outcome) in result.outcomes() {
+        let record = match outcome {
+            Ok(p) => JsonRecord {
+                day: result.day,
+                part: Some(part.number()),
+                answer: Some(&p.answer),
+                kind: Some(p.answer.kind()),
+                parse_time_ns: Some(result.parse_time.as_nanos()),
+                time_ns: Some(p.time.as_nanos()),
+                error: None,
+            },
+            Err(error) => JsonRecord {
+                day: result.day,
+                part: Some(part.number()),
+                answer: None,
+                kind: None,
+                parse_time_ns: Some(result.parse_time.as_nanos()),
+                time_ns: None,
+                error: Some(format!("{error:#}")),
+            },
+        };
+        println!("{}", serde_json::to_string(&record)?);
+    }
+    Ok(())
+}
+
+/// Print the error that prevented solving a day as a JSON object
+pub fn print_json_error(day: u8, error: &Report) -> Result<()> {
+    let record = JsonRecord {
+        day,
+        part: None,
+        answer: None,
+        kind: None,
+        parse_time_ns: None,
+        time_ns: None,
+        error: Some(format!("{error:#}")),
+    };
+    println!("{}", serde_json::to_string(&record)?);
+    Ok(())
+}
+
+/// Print a table with the time taken by each step of each day
+pub fn print_timings(results: &[&DayResult]) {
+    println!();
+    println!(
+        "{:>3} | {:>10} | {:>10} | {:>10} | {:>10}",
+        "Day", "Parse", "Part 1", "Part 2", "Total"
+    );
+    println!("{}", "-".repeat(4 + 13 * 4));
+    for result in results {
+        let part_time = |part| {
+            result
+                .part(part)
+                .map_or("-".to_string(), |p| format_duration(p.time))
+        };
+        println!(
+            "{:>3} | {:>10} | {:>10} | {:>10} | {:>10}",
+            result.day,
+            format_duration(result.parse_time),
+            part_time(Part::One),
+            part_time(Part::Two),
+            format_duration(result.total_time())
+        );
+    }
+    let total = results.iter().map(|r| r.total_time()).sum::<Duration>();
+    println!("Total: {}", format_duration(total));
+}
+
+/// What happened to a selected day
+#[derive(Debug)]
+pub enum Outcome<'a> {
+    Ran(&'a Result<DayResult>),
+    NotImplemented,
+}
+
+impl Outcome<'_> {
+    pub fn is_failure(&self) -> bool {
+        match self {
+            Outcome::Ran(Ok(result)) => !result.failures.is_empty(),
+            Outcome::Ran(Err(_)) => true,
+            Outcome::NotImplemented => false,
+        }
+    }
+
+    /// Describe what happened to the selected parts of the day
+    pub fn describe(&self, part: Option<Part>) -> String {
+        let parts = |status: &str| {
+            selected_parts(part)
+                .map(|p| format!("part {p} {status}"))
+                .join(", ")
+        };
+        match self {
+            Outcome::Ran(Ok(result)) if result.failures.is_empty() => "ok".to_string(),
+            Outcome::Ran(Ok(result)) => selected_parts(part)
+                .map(|p| {
+                    let status = if result.part(p).is_some() {
+                        "ok"
+                    } else {
+                        "failed"
+                    };
+                    format!("part {p} {status}")
+                })
+                .join(", "),
+            Outcome::Ran(Err(_)) => format!("failed, {}", parts("skipped")),
+            Outcome::NotImplemented => "skipped, not implemented yet".to_string(),
+        }
+    }
+}
+
+/// Print what happened to each selected day and part and return the number of days that failed
+pub fn print_summary(outcomes: &[(u8, Outcome)], part: Option<Part>) -> usize {
+    println!();
+    println!("Summary");
+    for (day, outcome) in outcomes {
+        println!("  Day {day:02}: {}", outcome.describe(part));
+    }
+
+    let failed = outcomes.iter().filter(|(_, o)| o.is_failure()).count();
+    let skipped = outcomes
+        .iter()
+        .filter(|(_, o)| matches!(o, Outcome::NotImplemented))
+        .count();
+    println!(
+        "{} succeeded, {failed} failed, {skipped} skipped",
+        outcomes.len() - failed - skipped
+    );
+    failed
+}
+
+/// Print a table with the min/median/max time taken by each step of each day
+pub fn print_bench(results: &[BenchResult], iterations: usize) {
+    println!("{iterations} iteration(s) per step");
+    println!(
+        "{:>3} | {:>6} | {:>10} | {:>10} | {:>10}",
+        "Day", "Step", "Min", "Median", "Max"
+    );
+    println!("{}", "-".repeat(3 + 9 + 13 * 3));
+    for result in results {
+        let steps = std::iter::once(("parse".to_string(), result.parse)).chain(
+            result
+                .parts
+                .iter()
+                .map(|(part, stats)| (format!("part {part}"), *stats)),
+        );
+        for (step, stats) in steps {
+            println!(
+                "{:>3} | {:>6} | {:>10} | {:>10} | {:>10}",
+                result.day,
+                step,
+                format_duration(stats.min),
+                format_duration(stats.median),
+                format_duration(stats.max)
+            );
+        }
+    }
+}
+
+/// Print how the answers of a day compare to the expected ones, or why they couldn't be
+/// computed, and return the verdicts of the answers
+pub fn print_verification(result: &DayResult, expected: &ExpectedAnswers) -> Vec<Verdict> {
+    let mut verdicts = Vec::new();
+    for (part, outcome) in result.outcomes() {
+        let p = match outcome {
+            Ok(p) => p,
+            Err(error) => {
+                println!(
+                    "Day {:02} part {part}: {}",
+                    result.day,
+                    indented_error(error)
+                );
+                continue;
+            }
+        };
+        let verdict = expected.verify(result.day, part, &p.answer);
+        let label = format!("Day {:02} part {part}: {verdict}", result.day);
+        match &verdict {
+            Verdict::Pass | Verdict::Missing => println!("{label} ({})", inline(&p.answer)),
+            Verdict::Fail { expected } => println!(
+                "{label} (expected {}, got {})",
+                inline(expected),
+                inline(&p.answer)
+            ),
+        }
+        verdicts.push(verdict);
+    }
+    verdicts
+}
+
+/// Render an answer on a single line
+fn inline(answer: &Answer) -> String {
+    match answer {
+        Answer::Text(s) if s.contains('\n') => format!("{s:?}"),
+        answer => answer.to_string(),
+    }
+}
+
+/// Format a duration with a unit suited to its magnitude
+pub fn format_duration(d: Duration) -> String {
+    let nanos = d.as_nanos();
+    if nanos < 1_000 {
+        format!("{nanos}ns")
+    } else if nanos < 1_000_000 {
+        format!("{:.1}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", d.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Solution;
+
+    struct Fake<const DAY: u8>;
+
+    impl<const DAY: u8> Solution for Fake<DAY> {
+        const DAY: u8 = DAY;
+
+        type Input = u64;
+
+        fn parse(data: &str) -> Result<Self::Input> {
+            Ok(data.parse()?)
+        }
+
+        fn part1(input: &Self::Input) -> Result<Answer> {
+            // Make the first days the slowest so they finish last
+            std::thread::sleep(Duration::from_millis(10 * (5 - DAY as u64)));
+            (*input + 1).try_into()
+        }
+
+        fn part2(input: &Self::Input) -> Result<Answer> {
+            (*input * 2).try_into()
+        }
+    }
+
+    #[test]
+    fn test_run_days_in_parallel() {
+        let solutions: [&dyn DynSolution; 4] = [&Fake::<1>, &Fake::<2>, &Fake::<3>, &Fake::<4>];
+        let results = run_days(&solutions, None, 4, |day| Ok(format!("{}", day * 10))).unwrap();
+
+        let answers = results
+            .into_iter()
+            .map(|r| {
+                let r = r.unwrap();
+                (r.day, r.parts[0].answer.clone(), r.parts[1].answer.clone())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            answers,
+            vec![
+                (1, Answer::Integer(11), Answer::Integer(20)),
+                (2, Answer::Integer(21), Answer::Integer(40)),
+                (3, Answer::Integer(31), Answer::Integer(60)),
+                (4, Answer::Integer(41), Answer::Integer(80)),
+            ]
+        );
+    }
+
+    struct Failing;
+
+    impl Solution for Failing {
+        const DAY: u8 = 5;
+
+        type Input = u64;
+
+        fn parse(data: &str) -> Result<Self::Input> {
+            Ok(data.parse()?)
+        }
+
+        fn part1(_: &Self::Input) -> Result<Answer> {
+            panic!("Oops")
+        }
+
+        fn part2(input: &Self::Input) -> Result<Answer> {
+            (*input * 2).try_into()
+        }
+    }
+
+    #[test]
+    fn test_failures_are_isolated() {
+        let solutions: [&dyn DynSolution; 3] = [&Fake::<1>, &Failing, &Fake::<3>];
+        let results = run_days(&solutions, None, 2, |day| match day {
+            3 => Ok("not a number".to_string()),
+            _ => Ok("21".to_string()),
+        })
+        .unwrap();
+
+        let day1 = results[0].as_ref().unwrap();
+        assert!(day1.failures.is_empty());
+
+        let day5 = results[1].as_ref().unwrap();
+        assert_eq!(day5.parts.len(), 1);
+        assert_eq!(day5.part(Part::Two).unwrap().answer, Answer::Integer(42));
+        assert_eq!(day5.failures.len(), 1);
+        assert_eq!(day5.failures[0].0, Part::One);
+        assert_eq!(day5.failures[0].1.to_string(), "Panicked: Oops");
+        // The failed part still comes first
+        let outcomes = day5
+            .outcomes()
+            .map(|(part, outcome)| (part, outcome.is_ok()))
+            .collect::<Vec<_>>();
+        assert_eq!(outcomes, [(Part::One, false), (Part::Two, true)]);
+        let outcome = Outcome::Ran(&results[1]);
+        assert!(outcome.is_failure());
+        assert_eq!(outcome.describe(None), "part 1 failed, part 2 ok");
+
+        assert!(results[2].is_err());
+        let outcome = Outcome::Ran(&results[2]);
+        assert_eq!(outcome.describe(Some(Part::Two)), "failed, part 2 skipped");
+        assert!(!Outcome::NotImplemented.is_failure());
+    }
+
+    #[test]
+    fn test_stats() {
+        let samples = [5, 1, 3, 2, 4].map(Duration::from_millis).to_vec();
+        let stats = Stats::from_samples(samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.max, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(999)), "999ns");
+        assert_eq!(format_duration(Duration::from_nanos(1_500)), "1.5µs");
+        assert_eq!(format_duration(Duration::from_micros(2_345)), "2.35ms");
+        assert_eq!(format_duration(Duration::from_millis(3_210)), "3.21s");
+    }
+
+    #[test]
+    fn test_json_record() {
+        let answer = Answer::from("CMZ");
+        let record = JsonRecord {
+            day: 5,
+            part: Some(1),
+            answer: Some(&answer),
+            kind: Some(answer.kind()),
+            parse_time_ns: Some(1200),
+            time_ns: Some(340),
+            error: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&record).unwrap(),
+            r#"{"day":5,"part":1,"answer":"CMZ","type":"text","parse_time_ns":1200,"time_ns":340,"error":null}"#
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! Generation of the files needed to start working on a new day
+
+use std::path::{Path, PathBuf};
+
+use eyre::{bail, eyre, Result, WrapErr};
+
+use crate::input;
+
+/// The module of a new day, where `{{DAY}}` is replaced by the zero-padded day number and
+/// `{{DAY_NUMBER}}` by the plain one
+pub const TEMPLATE: &str = include_str!("../templates/day.rs");
+
+/// Create the module of the given day from [`TEMPLATE`], register it in `src/lib.rs` and create
+/// empty input and example files, all relative to the project's `root`. Returns the files
+/// created or modified.
+pub fn new_day(root: &Path, day: u8) -> Result<Vec<PathBuf>> {
+    if !(1..=25).contains(&day) {
+        bail!("Invalid day {day}: must be between 1 and 25");
+    }
+
+    let module = root.join("src").join(format!("day{day:02}.rs"));
+    if module.exists() {
+        bail!("{} already exists", module.display());
+    }
+    let lib = root.join("src").join("lib.rs");
+    let lib_code = std::fs::read_to_string(&lib)
+        .wrap_err_with(|| format!("Failed to read {}", lib.display()))?;
+    let lib_code = register(&lib_code, day)?;
+
+    write(&module, &render(day))?;
+    write(&lib, &lib_code)?;
+    let mut files = vec![module, lib];
+    for dir in ["inputs", "examples"] {
+        let path = root.join(dir).join(input::file_name(day));
+        if !path.exists() {
+            std::fs::create_dir_all(root.join(dir))
+                .wrap_err_with(|| format!("Failed to create {dir}/"))?;
+            write(&path, "")?;
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// The code of the module of the given day
+pub fn render(day: u8) -> String {
+    TEMPLATE
+        .replace("{{DAY}}", &format!("{day:02}"))
+        .replace("{{DAY_NUMBER}}", &day.to_string())
+}
+
+/// Add the module of the given day and its solution to the code of `lib.rs`, keeping the days
+/// sorted
+pub fn register(lib_code: &str, day: u8) -> Result<String> {
+    let module = format!("day{day:02}");
+    let mod_line = format!("pub mod {module};");
+    let entry_line = format!("    &{module}::Day{day:02},");
+    if lib_code.lines().any(|line| line == mod_line) {
+        bail!("Day {day} is already registered");
+    }
+
+    let mut lines = lib_code.lines().map(str::to_string).collect::<Vec<_>>();
+
+    // The `pub mod` lines are sorted, the days coming before the other modules
+    let mod_pos = lines
+        .iter()
+        .position(|line| line.starts_with("pub mod ") && **line > *mod_line)
+        .ok_or_else(|| eyre!("No module declarations found"))?;
+    lines.insert(mod_pos, mod_line);
+
+    let array_start = lines
+        .iter()
+        .position(|line| line.starts_with("pub const DAYS: [&dyn DynSolution; "))
+        .ok_or_else(|| eyre!("No `DAYS` array found"))?;
+    let len = lines[array_start]
+        .strip_prefix("pub const DAYS: [&dyn DynSolution; ")
+        .and_then(|rest| rest.strip_suffix("] = ["))
+        .and_then(|len| len.parse::<usize>().ok())
+        .ok_or_else(|| eyre!("Unexpected declaration of `DAYS`"))?;
+    lines[array_start] = format!("pub const DAYS: [&dyn DynSolution; {}] = [", len + 1);
+
+    let array_end = array_start
+        + lines[array_start..]
+            .iter()
+            .position(|line| line == "];")
+            .ok_or_else(|| eyre!("Unterminated `DAYS` array"))?;
+    let entry_pos = (array_start + 1..array_end)
+        .find(|&i| lines[i] > entry_line)
+        .unwrap_or(array_end);
+    lines.insert(entry_pos, entry_line);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    std::fs::write(path, contents).wrap_err_with(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register() {
+        let lib_code = "\
+pub mod day01;
+pub mod day02;
+pub mod input;
+
+pub const DAYS: [&dyn DynSolution; 2] = [
+    &day01::Day01,
+    &day02::Day02,
+];
+";
+        assert_eq!(
+            register(lib_code, 14).unwrap(),
+            "\
+pub mod day01;
+pub mod day02;
+pub mod day14;
+pub mod input;
+
+pub const DAYS: [&dyn DynSolution; 3] = [
+    &day01::Day01,
+    &day02::Day02,
+    &day14::Day14,
+];
+"
+        );
+        assert!(register(lib_code, 2).is_err());
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join("src")).unwrap();
+        std::fs::write(root.path().join("src/lib.rs"), include_str!("lib.rs")).unwrap();
+
+        let files = new_day(root.path(), 25).unwrap();
+        assert_eq!(files.len(), 4);
+        let module = std::fs::read_to_string(root.path().join("src/day25.rs")).unwrap();
+        assert!(module.contains("impl Solution for Day25 {\n    const DAY: u8 = 25;"));
+        let lib_code = std::fs::read_to_string(root.path().join("src/lib.rs")).unwrap();
+        assert!(lib_code.contains("pub mod day25;\n"));
+        assert!(lib_code.contains("    &day25::Day25,\n];"));
+        assert!(root.path().join("inputs/day25.txt").exists());
+        assert!(root.path().join("examples/day25.txt").exists());
+
+        assert!(new_day(root.path(), 25).is_err());
+        assert!(new_day(root.path(), 26).is_err());
+    }
+}
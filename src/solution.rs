@@ -0,0 +1,192 @@
+use std::{fmt::Display, str::FromStr};
+
+use eyre::{eyre, Report, Result};
+use serde::{Deserialize, Serialize};
+
+/// A day's puzzle: how to parse its input and how to solve both of its parts
+pub trait Solution {
+    /// The day of the puzzle, between 1 and 25
+    const DAY: u8;
+
+    /// The model both parts are solved from
+    type Input;
+
+    fn parse(data: &str) -> Result<Self::Input>;
+
+    fn part1(input: &Self::Input) -> Result<Answer>;
+
+    fn part2(input: &Self::Input) -> Result<Answer>;
+}
+
+/// Object-safe version of [`Solution`], so that all the days can live in the same registry
+pub trait DynSolution: Sync {
+    fn day(&self) -> u8;
+
+    fn parse(&self, data: &str) -> Result<Box<dyn ParsedInput>>;
+}
+
+/// A day's input once parsed, ready to be solved. Both parts only need a shared reference to it,
+/// so they can be solved concurrently.
+pub trait ParsedInput: Send + Sync {
+    fn solve(&self, part: Part) -> Result<Answer>;
+}
+
+struct Parsed<S: Solution>(S::Input);
+
+impl<S> ParsedInput for Parsed<S>
+where
+    S: Solution,
+    S::Input: Send + Sync,
+{
+    fn solve(&self, part: Part) -> Result<Answer> {
+        match part {
+            Part::One => S::part1(&self.0),
+            Part::Two => S::part2(&self.0),
+        }
+    }
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution + Sync + 'static,
+    S::Input: Send + Sync + 'static,
+{
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn parse(&self, data: &str) -> Result<Box<dyn ParsedInput>> {
+        let input = S::parse(data)?;
+        Ok(Box::new(Parsed::<S>(input)))
+    }
+}
+
+/// The answer to one part of a puzzle
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+}
+
+impl Answer {
+    /// Name of the type of the answer, as reported in the JSON output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::Integer(_) => "integer",
+            Answer::Text(_) => "text",
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Integer(v) => write!(f, "{v}"),
+            Answer::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(v: $t) -> Self {
+                    Self::Integer(v.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(u8, u16, u32, i8, i16, i32, i64);
+
+/// Integers that may not fit in an `i64`: the conversion fails instead of wrapping around
+macro_rules! impl_try_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<$t> for Answer {
+                type Error = Report;
+
+                fn try_from(v: $t) -> Result<Self> {
+                    i64::try_from(v)
+                        .map(Self::Integer)
+                        .map_err(|_| eyre!("The answer {v} doesn't fit in a 64-bit signed integer"))
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_integer!(u64, usize, isize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Self::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Self::Text(s.to_string())
+    }
+}
+
+/// Which part of a day's puzzle to run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Self::One),
+            "2" => Ok(Self::Two),
+            _ => Err(eyre!("Invalid part {s:?}: must be 1 or 2")),
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.number())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part() {
+        assert_eq!("1".parse::<Part>().unwrap(), Part::One);
+        assert_eq!("2".parse::<Part>().unwrap(), Part::Two);
+        assert!("3".parse::<Part>().is_err());
+    }
+
+    #[test]
+    fn test_answer() {
+        assert_eq!(Answer::try_from(42usize).unwrap(), Answer::Integer(42));
+        assert_eq!(Answer::try_from(-3isize).unwrap().to_string(), "-3");
+        assert_eq!(Answer::from(7u32), Answer::Integer(7));
+        assert!(Answer::try_from(u64::MAX).is_err());
+        assert!(Answer::try_from(i64::MAX as usize + 1).is_err());
+        assert_eq!(Answer::from("CMZ").to_string(), "CMZ");
+    }
+}
@@ -0,0 +1,317 @@
+//! Submission of answers to the website, keeping track of the attempts so that an answer known
+//! to be wrong is never sent
+
+use std::{fmt::Display, path::Path, str::FromStr};
+
+use eyre::{bail, Result, WrapErr};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    fetch::Client,
+    solution::{Answer, Part},
+};
+
+/// Default location of the file recording the submitted answers
+pub const HISTORY_FILE: &str = "submissions.toml";
+
+/// How the website judged a submitted answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feedback {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without any hint
+    Wrong,
+}
+
+impl Display for Feedback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Feedback::Correct => "correct",
+            Feedback::TooHigh => "too high",
+            Feedback::TooLow => "too low",
+            Feedback::Wrong => "wrong",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// What the website replied to a submission
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    Judged(Feedback),
+    /// The previous submission was too recent, along with how long to wait if known
+    TooRecent(Option<String>),
+    /// The part is already solved, or part 1 isn't yet
+    WrongLevel,
+    /// Anything else, with the text of the page
+    Unknown(String),
+}
+
+impl Response {
+    /// Make sense of the HTML page returned after a submission
+    pub fn parse(html: &str) -> Self {
+        let text = article_text(html);
+        let lower = text.to_lowercase();
+        if lower.contains("that's the right answer") {
+            Response::Judged(Feedback::Correct)
+        } else if lower.contains("too high") {
+            Response::Judged(Feedback::TooHigh)
+        } else if lower.contains("too low") {
+            Response::Judged(Feedback::TooLow)
+        } else if lower.contains("that's not the right answer") {
+            Response::Judged(Feedback::Wrong)
+        } else if lower.contains("you gave an answer too recently") {
+            let wait = lower
+                .split_once("you have ")
+                .and_then(|(_, rest)| rest.split_once(" left to wait"))
+                .map(|(wait, _)| wait.to_string());
+            Response::TooRecent(wait)
+        } else if lower.contains("you don't seem to be solving the right level") {
+            Response::WrongLevel
+        } else {
+            Response::Unknown(text)
+        }
+    }
+}
+
+/// The text of the `<article>` of the page (or of the whole page if there is none), without the
+/// tags
+fn article_text(html: &str) -> String {
+    // Keep the opening tag's `<` so that its attributes are skipped
+    let article = html
+        .find("<article")
+        .and_then(|start| {
+            let end = html[start..].find("</article>")?;
+            Some(&html[start..start + end])
+        })
+        .unwrap_or(html);
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A judged submission
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attempt {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Answer,
+    pub feedback: Feedback,
+    /// When the answer was submitted, in seconds since the Unix epoch
+    pub time: u64,
+}
+
+/// All the judged submissions, as recorded in `submissions.toml`:
+///
+/// ```toml
+/// [[attempt]]
+/// day = 1
+/// part = 1
+/// answer = 72241
+/// feedback = "too_high"
+/// time = 1670000000
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct History {
+    #[serde(default, rename = "attempt")]
+    pub attempts: Vec<Attempt>,
+}
+
+impl History {
+    /// Load the history from the given file, which may not exist yet
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read submissions from {}", path.display()))?;
+        data.parse()
+            .wrap_err_with(|| format!("Invalid submissions file {}", path.display()))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, toml::to_string(self)?)
+            .wrap_err_with(|| format!("Failed to write submissions to {}", path.display()))
+    }
+
+    /// The previous attempts for the given day and part
+    pub fn attempts(&self, day: u8, part: Part) -> impl Iterator<Item = &Attempt> {
+        self.attempts
+            .iter()
+            .filter(move |a| a.day == day && a.part == part.number())
+    }
+
+    /// Check an answer against the previous attempts, failing if it is already known to be
+    /// wrong (or right)
+    pub fn check(&self, day: u8, part: Part, answer: &Answer) -> Result<()> {
+        for attempt in self.attempts(day, part) {
+            let previous = &attempt.answer;
+            if previous == answer {
+                bail!(
+                    "{answer} was already submitted for day {day} part {part}: it was {}",
+                    attempt.feedback
+                );
+            }
+            match (attempt.feedback, previous, answer) {
+                (Feedback::Correct, _, _) => {
+                    bail!("Day {day} part {part} is already solved, the answer was {previous}")
+                }
+                (Feedback::TooHigh, Answer::Integer(high), Answer::Integer(a)) if a >= high => {
+                    bail!("{answer} is too high: {previous} already was")
+                }
+                (Feedback::TooLow, Answer::Integer(low), Answer::Integer(a)) if a <= low => {
+                    bail!("{answer} is too low: {previous} already was")
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for History {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(toml::from_str(s)?)
+    }
+}
+
+/// Submit an answer unless the history tells it is wrong, and record the website's judgement in
+/// the history
+pub fn submit(
+    client: &mut Client,
+    history: &mut History,
+    day: u8,
+    part: Part,
+    answer: &Answer,
+) -> Result<Feedback> {
+    let text = answer.to_string();
+    if text.contains('\n') {
+        bail!("Can't submit an answer spanning several lines, read it and submit it explicitly");
+    }
+    history.check(day, part, answer)?;
+
+    let level = part.number().to_string();
+    let html = client
+        .post_form(
+            &format!("day/{day}/answer"),
+            &[("level", &level), ("answer", &text)],
+        )
+        .wrap_err_with(|| format!("Failed to submit the answer of day {day} part {part}"))?;
+
+    match Response::parse(&html) {
+        Response::Judged(feedback) => {
+            history.attempts.push(Attempt {
+                day,
+                part: part.number(),
+                answer: answer.clone(),
+                feedback,
+                time: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs()),
+            });
+            Ok(feedback)
+        }
+        Response::TooRecent(Some(wait)) => bail!("Answer submitted too recently, wait {wait}"),
+        Response::TooRecent(None) => bail!("Answer submitted too recently"),
+        Response::WrongLevel => {
+            bail!("Day {day} part {part} can't be submitted: already solved, or part 1 isn't")
+        }
+        Response::Unknown(text) => bail!("Unexpected response: {text}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(part: u8, answer: impl Into<Answer>, feedback: Feedback) -> Attempt {
+        Attempt {
+            day: 1,
+            part,
+            answer: answer.into(),
+            feedback,
+            time: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let page = |p: &str| format!("<html><main><article><p>{p}</p></article></main></html>");
+        assert_eq!(
+            Response::parse(&page(
+                "That's the right answer! You are <em>one gold star</em> closer."
+            )),
+            Response::Judged(Feedback::Correct)
+        );
+        assert_eq!(
+            Response::parse(&page(
+                "That's not the right answer; your answer is too high."
+            )),
+            Response::Judged(Feedback::TooHigh)
+        );
+        assert_eq!(
+            Response::parse(&page(
+                "That's not the right answer; your answer is too low."
+            )),
+            Response::Judged(Feedback::TooLow)
+        );
+        assert_eq!(
+            Response::parse(&page("That's not the right answer. If you're stuck, ...")),
+            Response::Judged(Feedback::Wrong)
+        );
+        assert_eq!(
+            Response::parse(&page(
+                "You gave an answer too recently; you have 38s left to wait."
+            )),
+            Response::TooRecent(Some("38s".to_string()))
+        );
+        assert_eq!(
+            Response::parse(&page("You don't seem to be solving the right level.")),
+            Response::WrongLevel
+        );
+        assert_eq!(
+            Response::parse("<p>Down  for\nmaintenance</p>"),
+            Response::Unknown("Down for maintenance".to_string())
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let history = History {
+            attempts: vec![
+                attempt(1, 100, Feedback::TooHigh),
+                attempt(1, 10, Feedback::TooLow),
+                attempt(1, 50, Feedback::Wrong),
+                attempt(2, "ABC", Feedback::Correct),
+            ],
+        };
+        assert!(history.check(1, Part::One, &Answer::from(42)).is_ok());
+        assert!(history.check(1, Part::One, &Answer::from(50)).is_err());
+        assert!(history.check(1, Part::One, &Answer::from(100)).is_err());
+        assert!(history.check(1, Part::One, &Answer::from(150)).is_err());
+        assert!(history.check(1, Part::One, &Answer::from(5)).is_err());
+        assert!(history.check(1, Part::Two, &Answer::from("ABD")).is_err());
+        assert!(history.check(2, Part::One, &Answer::from(100)).is_ok());
+
+        let roundtrip = toml::to_string(&history)
+            .unwrap()
+            .parse::<History>()
+            .unwrap();
+        assert_eq!(roundtrip, history);
+    }
+}
@@ -0,0 +1,666 @@
+//! Step-by-step pictures of the days simulating something: the crane of day 5, the rope of day 9,
+//! the CRT of day 10 and the path search of day 12. Each day draws its frames as text, which a
+//! [`Screen`] then shows in the terminal, saves for later or records as an animated GIF.
+
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    fmt::Display,
+    io::{BufRead, Write},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+use eyre::{bail, eyre, Result};
+use itertools::Itertools;
+
+use crate::{
+    day05::{Day05, Stacks},
+    day09::{self, Day09},
+    day10::{Cpu, Day10},
+    day12::Day12,
+    export::{gradient, Rgb},
+    geometry::{BoundingBox, IPoint, Point, UPoint},
+    grid::Grid,
+    solution::{Part, Solution},
+};
+
+/// The days that can be visualised
+pub const DAYS: [u8; 4] = [5, 9, 10, 12];
+
+/// Largest area of the plane shown at once for day 9, whose rope wanders far
+const MAX_VIEW: (usize, usize) = (80, 30);
+
+/// A picture of the simulation at one step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// What happened during the step
+    pub caption: String,
+    pub cells: Grid<char>,
+}
+
+impl Frame {
+    /// A frame showing lines of text, padded with spaces to the longest one
+    pub fn from_lines<S: AsRef<str>>(caption: impl Into<String>, lines: &[S]) -> Self {
+        let rows = lines
+            .iter()
+            .map(|line| line.as_ref().chars().collect_vec())
+            .collect_vec();
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let cells = Grid::from_fn(width, rows.len(), |p| {
+            rows[p.y].get(p.x).copied().unwrap_or(' ')
+        });
+        Self {
+            caption: caption.into(),
+            cells,
+        }
+    }
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.caption)?;
+        let rows = self.cells.rows().map(|row| row.iter().collect::<String>());
+        write!(f, "{}", rows.format("\n"))
+    }
+}
+
+/// Where the frames are shown
+pub trait Screen {
+    /// Show the next frame, returning whether the viewer wants to see more
+    fn show(&mut self, frame: &Frame) -> Result<bool>;
+}
+
+/// A command typed by the viewer, followed by Enter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    /// `p`: pause or resume
+    TogglePause,
+    /// Enter alone: pause, or show the next frame if already paused
+    Step,
+    /// `+`: double the frame rate
+    Faster,
+    /// `-`: halve the frame rate
+    Slower,
+    /// `q`: stop the visualisation
+    Quit,
+}
+
+/// Draws the frames in the terminal at a steady rate, reading the controls from stdin
+pub struct Terminal {
+    delay: Duration,
+    paused: bool,
+    controls: Receiver<Control>,
+}
+
+impl Terminal {
+    const HELP: &'static str = "[p] pause/resume  [Enter] step  [+/-] speed  [q] quit";
+    /// Bounds of the delay between frames, whatever the frame rate asked for
+    const MIN_DELAY: Duration = Duration::from_millis(1);
+    const MAX_DELAY: Duration = Duration::from_secs(3600);
+
+    pub fn new(fps: f64) -> Self {
+        let (sender, controls) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in std::io::stdin().lock().lines() {
+                let control = match line.as_deref().map(str::trim) {
+                    Ok("") => Control::Step,
+                    Ok("p") => Control::TogglePause,
+                    Ok("+") => Control::Faster,
+                    Ok("-") => Control::Slower,
+                    Ok("q") => Control::Quit,
+                    Ok(_) => continue,
+                    Err(_) => break,
+                };
+                if sender.send(control).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            delay: Duration::from_secs_f64(
+                (1.0 / fps).clamp(Self::MIN_DELAY.as_secs_f64(), Self::MAX_DELAY.as_secs_f64()),
+            ),
+            paused: false,
+            controls,
+        }
+    }
+
+    /// Wait until the next frame is due, applying the controls typed meanwhile. Returns whether
+    /// to go on.
+    fn wait(&mut self) -> bool {
+        let due = Instant::now() + self.delay;
+        loop {
+            let control = if self.paused {
+                match self.controls.recv() {
+                    Ok(control) => control,
+                    // Nobody can resume anymore
+                    Err(_) => {
+                        self.paused = false;
+                        continue;
+                    }
+                }
+            } else {
+                match self
+                    .controls
+                    .recv_timeout(due.saturating_duration_since(Instant::now()))
+                {
+                    Ok(control) => control,
+                    Err(RecvTimeoutError::Timeout) => return true,
+                    // Without stdin, keep playing
+                    Err(RecvTimeoutError::Disconnected) => {
+                        std::thread::sleep(due.saturating_duration_since(Instant::now()));
+                        return true;
+                    }
+                }
+            };
+            match control {
+                Control::TogglePause => self.paused = !self.paused,
+                Control::Step if self.paused => return true,
+                Control::Step => self.paused = true,
+                Control::Faster => self.delay = (self.delay / 2).max(Self::MIN_DELAY),
+                Control::Slower => self.delay = (self.delay * 2).min(Self::MAX_DELAY),
+                Control::Quit => return false,
+            }
+        }
+    }
+}
+
+impl Screen for Terminal {
+    fn show(&mut self, frame: &Frame) -> Result<bool> {
+        let mut out = std::io::stdout().lock();
+        // Clear the screen and go back to its top left corner
+        write!(out, "\x1b[2J\x1b[H{frame}\n\n{}\n", Self::HELP)?;
+        out.flush()?;
+        drop(out);
+        Ok(self.wait())
+    }
+}
+
+/// Writes every frame one after the other, without waiting, for instance to a file to look at
+/// the whole simulation or check it in a test
+pub struct TextFile<W: Write> {
+    out: W,
+    count: usize,
+}
+
+impl<W: Write> TextFile<W> {
+    pub fn new(out: W) -> Self {
+        Self { out, count: 0 }
+    }
+
+    /// Number of frames written so far
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> Screen for TextFile<W> {
+    fn show(&mut self, frame: &Frame) -> Result<bool> {
+        self.count += 1;
+        writeln!(self.out, "Frame {}: {frame}\n", self.count)?;
+        Ok(true)
+    }
+}
+
+/// Records the frames as an animated GIF, each cell being a square of the colour the day's
+/// [`palette`] gives it. The frames are kept in memory until [`Gif::finish`] writes them.
+pub struct Gif {
+    palette: fn(char) -> Rgb,
+    scale: usize,
+    skip: usize,
+    /// How long each frame is shown, in hundredths of a second
+    delay: u16,
+    /// The recorded frames, made of indices in `colours`
+    frames: Vec<Grid<u8>>,
+    colours: Vec<Rgb>,
+    /// The last frame shown if it was skipped, to end the animation on it
+    last: Option<Grid<u8>>,
+    count: usize,
+}
+
+impl Gif {
+    /// Record one frame out of `skip + 1`, played at `fps` frames per second
+    pub fn new(palette: fn(char) -> Rgb, scale: usize, skip: usize, fps: f64) -> Self {
+        Self {
+            palette,
+            scale,
+            skip,
+            delay: (100.0 / fps).round().clamp(2.0, u16::MAX as f64) as u16,
+            frames: Vec::new(),
+            colours: Vec::new(),
+            last: None,
+            count: 0,
+        }
+    }
+
+    /// The index of the colour in the palette of the GIF, adding it if needed
+    fn colour_index(&mut self, colour: Rgb) -> Result<u8> {
+        let i = match self.colours.iter().position(|&c| c == colour) {
+            Some(i) => i,
+            None => {
+                self.colours.push(colour);
+                self.colours.len() - 1
+            }
+        };
+        u8::try_from(i).map_err(|_| eyre!("A GIF can't have more than 256 colours"))
+    }
+
+    /// Write the recorded frames, returning how many there are. The animation loops forever,
+    /// pausing on its last frame.
+    pub fn finish(mut self, out: impl Write) -> Result<usize> {
+        self.frames.extend(self.last.take());
+        let background = self.colour_index((self.palette)(' '))?;
+        let size = |n: usize| {
+            u16::try_from(n * self.scale).map_err(|_| eyre!("The frames are too large for a GIF"))
+        };
+        let width = size(self.frames.iter().map(Grid::width).max().unwrap_or(0))?;
+        let height = size(self.frames.iter().map(Grid::height).max().unwrap_or(0))?;
+        if width == 0 || height == 0 {
+            bail!("There are no frames to record");
+        }
+
+        let palette = self.colours.concat();
+        let mut encoder = gif::Encoder::new(out, width, height, &palette)?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        for (i, frame) in self.frames.iter().enumerate() {
+            let mut pixels = vec![background; width as usize * height as usize];
+            for (p, &colour) in frame.iter() {
+                for dy in 0..self.scale {
+                    let start = (p.y * self.scale + dy) * width as usize + p.x * self.scale;
+                    pixels[start..start + self.scale].fill(colour);
+                }
+            }
+            let mut frame = gif::Frame::from_indexed_pixels(width, height, pixels, None);
+            frame.delay = match i + 1 == self.frames.len() {
+                true => self.delay.max(200),
+                false => self.delay,
+            };
+            encoder.write_frame(&frame)?;
+        }
+        encoder.into_inner()?.flush()?;
+        Ok(self.frames.len())
+    }
+}
+
+impl Screen for Gif {
+    fn show(&mut self, frame: &Frame) -> Result<bool> {
+        let mut cells = Grid::from_fn(frame.cells.width(), frame.cells.height(), |_| 0);
+        for (p, &c) in frame.cells.iter() {
+            cells[p] = self.colour_index((self.palette)(c))?;
+        }
+        if self.count.is_multiple_of(self.skip + 1) {
+            self.frames.push(cells);
+            self.last = None;
+        } else {
+            self.last = Some(cells);
+        }
+        self.count += 1;
+        Ok(true)
+    }
+}
+
+const BACKGROUND: Rgb = [25, 25, 30];
+
+/// The colour of each character of the given day's frames
+pub fn palette(day: u8) -> fn(char) -> Rgb {
+    match day {
+        // Crates in brown on their stacks, with the stack numbers in grey
+        5 => |c| match c {
+            'A'..='Z' => [230, 170, 90],
+            '[' | ']' => [140, 90, 40],
+            '1'..='9' => [150, 150, 150],
+            _ => BACKGROUND,
+        },
+        // The head in yellow, the other knots in white, the positions visited by the tail in
+        // blue and the start in red
+        9 => |c| match c {
+            'H' => [255, 200, 0],
+            '#' => [40, 90, 200],
+            's' => [220, 40, 40],
+            '.' | ' ' => BACKGROUND,
+            _ => [240, 240, 240],
+        },
+        // The lit pixels and the sprite in amber
+        10 => |c| match c {
+            '#' => [255, 190, 40],
+            _ => BACKGROUND,
+        },
+        // The elevations from green to white, the reached positions in blue, the new ones in
+        // yellow and the path found in red
+        12 => |c| match c {
+            'a'..='z' => gradient(
+                [30, 90, 50],
+                [240, 240, 230],
+                (c as u8 - b'a') as f64 / 25.0,
+            ),
+            'S' | 'E' => [255, 255, 255],
+            '.' => [60, 90, 170],
+            '*' => [255, 210, 0],
+            '#' => [220, 30, 30],
+            _ => BACKGROUND,
+        },
+        _ => |c| match c {
+            ' ' | '.' => BACKGROUND,
+            _ => [240, 240, 240],
+        },
+    }
+}
+
+/// Show the simulation of the given day and part, from its input
+pub fn visualize(day: u8, data: &str, part: Part, screen: &mut dyn Screen) -> Result<()> {
+    match day {
+        5 => day05(data, part, screen),
+        9 => day09(data, part, screen),
+        10 => day10(data, screen),
+        12 => day12(data, part, screen),
+        _ => bail!(
+            "Day {day} has no visualisation, only days {} do",
+            DAYS.iter().join(", ")
+        ),
+    }
+}
+
+/// The crane rearranging the stacks, one move at a time
+fn day05(data: &str, part: Part, screen: &mut dyn Screen) -> Result<()> {
+    let (mut stacks, moves) = Day05::parse(data)?;
+    let crane = match part {
+        Part::One => "CrateMover 9000",
+        Part::Two => "CrateMover 9001",
+    };
+    let draw = |stacks: &Stacks, caption: String| Frame::from_lines(caption, &stacks.drawing());
+
+    if !screen.show(&draw(
+        &stacks,
+        format!("{crane}, top crates: {}", stacks.top_crates()),
+    ))? {
+        return Ok(());
+    }
+    for (i, m) in moves.iter().enumerate() {
+        match part {
+            Part::One => stacks.apply_move1(m)?,
+            Part::Two => stacks.apply_move2(m)?,
+        }
+        let caption = format!(
+            "{crane}, move {}/{}: {} from {} to {}, top crates: {}",
+            i + 1,
+            moves.len(),
+            m.0,
+            m.1,
+            m.2,
+            stacks.top_crates()
+        );
+        if !screen.show(&draw(&stacks, caption))? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// The knots of the rope following its head, one step at a time
+fn day09(data: &str, part: Part, screen: &mut dyn Screen) -> Result<()> {
+    let moves = Day09::parse(data)?;
+    match part {
+        Part::One => rope::<1>(&moves, screen),
+        Part::Two => rope::<9>(&moves, screen),
+    }
+}
+
+fn rope<const N: usize>(moves: &day09::Moves, screen: &mut dyn Screen) -> Result<()> {
+    let mut rope = day09::Grid::<N>::new();
+    // Once the rope has been everywhere, only show the area around its head
+    let mut seen = BoundingBox::new(IPoint::default());
+    let mut steps = 0;
+    for (i, &day09::Move(dir, num)) in moves.0.iter().enumerate() {
+        for _ in 0..num {
+            rope.move_head(dir);
+            steps += 1;
+            rope.tails().iter().for_each(|&knot| seen.extend(knot));
+            seen.extend(rope.head());
+
+            let view = view_around(seen, rope.head());
+            let mut cells = Grid::from_fn(view.width(), view.height(), |_| '.');
+            let mut draw = |p: IPoint, c| {
+                if let Ok(p) = UPoint::try_from(p - view.min) {
+                    if let Some(cell) = cells.get_mut(p) {
+                        *cell = c;
+                    }
+                }
+            };
+            rope.tail_positions().iter().for_each(|&p| draw(p, '#'));
+            draw(IPoint::default(), 's');
+            // Knots closer to the head hide the ones after them
+            for (k, &knot) in rope.tails().iter().enumerate().rev() {
+                let name = match N {
+                    1 => 'T',
+                    _ => char::from_digit(k as u32 + 1, 10).unwrap_or('*'),
+                };
+                draw(knot, name);
+            }
+            draw(rope.head(), 'H');
+
+            let caption = format!(
+                "Move {}/{}: {dir:?} {num}, step {steps}, the tail visited {} positions",
+                i + 1,
+                moves.0.len(),
+                rope.tail_positions().len()
+            );
+            if !screen.show(&Frame { caption, cells })? {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The part of the box to show, centered on the given point if it is too large
+fn view_around(bbox: BoundingBox<isize>, center: IPoint) -> BoundingBox<isize> {
+    let axis = |min: isize, max: isize, center: isize, size: usize| {
+        let size = size as isize;
+        if max - min < size {
+            (min, max)
+        } else {
+            let start = (center - size / 2).clamp(min, max - size + 1);
+            (start, start + size - 1)
+        }
+    };
+    let (min_x, max_x) = axis(bbox.min.x, bbox.max.x, center.x, MAX_VIEW.0);
+    let (min_y, max_y) = axis(bbox.min.y, bbox.max.y, center.y, MAX_VIEW.1);
+    BoundingBox {
+        min: Point::new(min_x, min_y),
+        max: Point::new(max_x, max_y),
+    }
+}
+
+/// The CRT drawing the screen, one cycle at a time, along with the sprite
+fn day10(data: &str, screen: &mut dyn Screen) -> Result<()> {
+    let program = Day10::parse(data)?;
+    let mut cpu = Cpu::new();
+    let mut result = Ok(true);
+    for inst in &program {
+        let step = cpu.step_with(inst, |cpu| {
+            if !matches!(result, Ok(true)) {
+                return;
+            }
+            let crt = cpu.crt();
+            let mut lines = (0..6)
+                .map(|y| {
+                    (0..40)
+                        .map(|x| if crt.is_lit(x, y) { '#' } else { '.' })
+                        .collect::<String>()
+                })
+                .collect_vec();
+            let sprite = (0..40)
+                .map(|x: isize| if cpu.x().abs_diff(x) <= 1 { '#' } else { ' ' })
+                .collect::<String>();
+            lines.push(String::new());
+            lines.push(sprite);
+            let caption = format!(
+                "Cycle {}, sprite at X = {}, signal strength {}",
+                cpu.cycles(),
+                cpu.x(),
+                cpu.signal().last().copied().unwrap_or_default()
+            );
+            result = screen.show(&Frame::from_lines(caption, &lines));
+        });
+        if !result? {
+            break;
+        }
+        step?;
+        result = Ok(true);
+    }
+    Ok(())
+}
+
+/// The breadth-first search for the goal, one distance at a time, then the path found
+fn day12(data: &str, part: Part, screen: &mut dyn Screen) -> Result<()> {
+    let map = Day12::parse(data)?;
+    let starts = match part {
+        Part::One => vec![map.start()],
+        Part::Two => map.starting_positions(),
+    };
+    let draw = |visited: &HashMap<UPoint, Option<UPoint>>, frontier: &HashSet<UPoint>| {
+        let mut cells = map.grid().map(|&c| c as char);
+        for &p in visited.keys() {
+            cells[p] = '.';
+        }
+        for &p in frontier {
+            cells[p] = '*';
+        }
+        cells
+    };
+
+    // The position each visited one was reached from
+    let mut visited = starts.iter().map(|&p| (p, None)).collect::<HashMap<_, _>>();
+    let mut frontier = starts.into_iter().collect::<HashSet<_>>();
+    let mut steps = 0;
+    while !frontier.is_empty() && !visited.contains_key(&map.goal()) {
+        let caption = format!(
+            "{steps} steps: {} positions reached, {} new",
+            visited.len(),
+            frontier.len()
+        );
+        let cells = draw(&visited, &frontier);
+        if !screen.show(&Frame { caption, cells })? {
+            return Ok(());
+        }
+        let mut next = HashSet::new();
+        for &p in &frontier {
+            for n in map.neighbours(p) {
+                if let Entry::Vacant(entry) = visited.entry(n) {
+                    entry.insert(Some(p));
+                    next.insert(n);
+                }
+            }
+        }
+        frontier = next;
+        steps += 1;
+    }
+
+    let mut cells = draw(&visited, &HashSet::new());
+    let caption = match visited.get(&map.goal()) {
+        Some(_) => {
+            let mut pos = map.goal();
+            while let Some(Some(previous)) = visited.get(&pos) {
+                cells[pos] = '#';
+                pos = *previous;
+            }
+            cells[pos] = '#';
+            format!("Reached the goal in {steps} steps")
+        }
+        None => format!(
+            "The goal can't be reached, {} positions visited",
+            visited.len()
+        ),
+    };
+    screen.show(&Frame { caption, cells })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// All the frames of a simulation
+    fn frames(day: u8, data: &str, part: Part) -> Vec<String> {
+        let mut screen = TextFile::new(Vec::new());
+        visualize(day, data, part, &mut screen).unwrap();
+        let text = String::from_utf8(screen.into_inner()).unwrap();
+        text.split("Frame ").skip(1).map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_day05() {
+        let frames = frames(5, include_str!("../examples/day05.txt"), Part::Two);
+        assert_eq!(frames.len(), 5);
+        assert_eq!(
+            frames[4],
+            "5: CrateMover 9001, move 4/4: 1 from 1 to 2, top crates: MCD\n        [D]\n        \
+             [N]\n        [Z]\n[M] [C] [P]\n 1   2   3 \n\n"
+        );
+    }
+
+    #[test]
+    fn test_day09() {
+        let frames = frames(9, include_str!("../examples/day09.txt"), Part::One);
+        assert_eq!(frames.len(), 24);
+        assert_eq!(
+            frames[23],
+            "24: Move 8/8: Right 2, step 24, the tail visited 13 positions\n\
+             ..##..\n...##.\n.TH##.\n....#.\ns###..\n\n"
+        );
+    }
+
+    #[test]
+    fn test_day10() {
+        let frames = frames(10, include_str!("../examples/day10.txt"), Part::Two);
+        assert_eq!(frames.len(), 240);
+        assert!(frames[239].contains("\n#######.......#######.......#######.....\n"));
+    }
+
+    #[test]
+    fn test_day12() {
+        let frames = frames(12, include_str!("../examples/day12.txt"), Part::One);
+        assert_eq!(frames.len(), 32);
+        assert!(frames[31].starts_with("32: Reached the goal in 31 steps\n"));
+        assert_eq!(frames[31].matches('#').count(), 32);
+    }
+
+    #[test]
+    fn test_gif() {
+        let data = include_str!("../examples/day09.txt");
+        let mut gif = Gif::new(palette(9), 3, 4, 10.0);
+        visualize(9, data, Part::Two, &mut gif).unwrap();
+        let mut out = Vec::new();
+        // 24 steps: frames 1, 6, 11, 16, 21 and the last one
+        assert_eq!(gif.finish(&mut out).unwrap(), 6);
+
+        let mut decoder = gif::DecodeOptions::new();
+        decoder.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = decoder.read_info(out.as_slice()).unwrap();
+        // The view grows up to the 6x5 area covered by the rope
+        assert_eq!((decoder.width(), decoder.height()), (18, 15));
+        let mut delays = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            delays.push(frame.delay);
+        }
+        assert_eq!(delays, [10, 10, 10, 10, 10, 200]);
+
+        assert!(Gif::new(palette(9), 3, 0, 10.0).finish(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_view() {
+        let bbox = BoundingBox {
+            min: Point::new(-100, 0),
+            max: Point::new(100, 10),
+        };
+        let view = view_around(bbox, Point::new(95, 5));
+        assert_eq!((view.width(), view.height()), (MAX_VIEW.0, 11));
+        assert_eq!(view.max.x, 100);
+        assert!(visualize(1, "", Part::One, &mut TextFile::new(Vec::new())).is_err());
+    }
+}
@@ -0,0 +1,261 @@
+//! Re-running a day whenever its source, input or examples change. Since the source changes, the
+//! day is rebuilt and run by a new `cargo` process each time.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::{Duration, SystemTime},
+};
+
+use eyre::{bail, Result, WrapErr};
+use serde::Deserialize;
+
+use crate::{
+    answers::EXAMPLE_ANSWERS_FILE,
+    input::InputDirs,
+    solution::{Answer, Part},
+};
+
+/// How often the files are checked for changes
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The files the given day depends on: its module, its input and its examples
+pub fn watched_files(day: u8, dirs: &InputDirs) -> Vec<PathBuf> {
+    let mut files = vec![PathBuf::from(format!("src/day{day:02}.rs"))];
+    files.extend(dirs.candidates(day));
+    files.push(PathBuf::from(EXAMPLE_ANSWERS_FILE));
+
+    // Besides `dayNN.txt`, a day can have more examples such as `dayNN-larger.txt`
+    let prefix = format!("day{day:02}");
+    let mut examples = std::fs::read_dir("examples")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path.file_name().and_then(|name| name.to_str());
+            name.is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".txt"))
+        })
+        .collect::<Vec<_>>();
+    examples.sort();
+    files.extend(examples);
+    files
+}
+
+/// The modification times of a set of files, `None` for those that don't exist
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot(BTreeMap<PathBuf, Option<SystemTime>>);
+
+impl Snapshot {
+    pub fn take(files: &[PathBuf]) -> Self {
+        let mtimes = files
+            .iter()
+            .map(|path| {
+                let mtime = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+                (path.clone(), mtime)
+            })
+            .collect();
+        Self(mtimes)
+    }
+
+    /// The files that were created, modified or deleted since the previous snapshot
+    pub fn changes(&self, previous: &Snapshot) -> Vec<&Path> {
+        self.0
+            .iter()
+            .filter(|(path, mtime)| previous.0.get(*path) != Some(mtime))
+            .map(|(path, _)| path.as_path())
+            .collect()
+    }
+}
+
+/// The outcome of each part of a run: the answer, or why it couldn't be computed
+pub type Answers = BTreeMap<Part, Result<Answer, String>>;
+
+#[derive(Debug, Deserialize)]
+struct Record {
+    part: Option<u8>,
+    answer: Option<Answer>,
+    error: Option<String>,
+}
+
+/// Read the answers from the output of `run --format json`. An error without a part prevented
+/// solving both.
+pub fn parse_answers(output: &str) -> Answers {
+    let mut answers = Answers::new();
+    for record in output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Record>(line).ok())
+    {
+        let outcome = match (record.answer, record.error) {
+            (Some(answer), _) => Ok(answer),
+            (None, error) => Err(error.unwrap_or_else(|| "no answer".to_string())),
+        };
+        let parts: &[Part] = match record.part {
+            Some(1) => &[Part::One],
+            Some(2) => &[Part::Two],
+            _ => &Part::ALL,
+        };
+        answers.extend(parts.iter().map(|part| (*part, outcome.clone())));
+    }
+    answers
+}
+
+/// Describe the answers of each part, compared to the previous run if there was one
+pub fn describe_changes(previous: Option<&Answers>, current: &Answers) -> Vec<String> {
+    let describe = |outcome: &Result<Answer, String>| match outcome {
+        Ok(answer) => answer.to_string(),
+        Err(e) => format!("error: {e}"),
+    };
+    Part::ALL
+        .iter()
+        .filter_map(|part| {
+            let now = current.get(part)?;
+            let change = match previous.map(|answers| answers.get(part)) {
+                None => String::new(),
+                Some(None) => " (new)".to_string(),
+                Some(Some(before)) if before == now => " (unchanged)".to_string(),
+                Some(Some(before)) => format!(" (was {})", describe(before)),
+            };
+            Some(format!("Part {part}: {}{change}", describe(now)))
+        })
+        .collect()
+}
+
+/// Rebuild and run the day's parts, returning their answers, or `None` if the build failed
+fn run_day(day: u8) -> Result<Option<Answers>> {
+    let output = cargo()
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "run",
+            &day.to_string(),
+            "--format",
+            "json",
+        ])
+        .stderr(Stdio::inherit())
+        .output()
+        .wrap_err("Failed to run cargo")?;
+    let answers = parse_answers(&String::from_utf8_lossy(&output.stdout));
+    // Failing parts make the run fail too, but still print their records
+    Ok((output.status.success() || !answers.is_empty()).then_some(answers))
+}
+
+/// Run the unit and example tests of the day, returning whether they passed
+fn test_day(day: u8) -> Result<bool> {
+    let status = cargo()
+        .args(["test", "--quiet", &format!("day{day:02}")])
+        .stdout(Stdio::null())
+        .status()
+        .wrap_err("Failed to run cargo")?;
+    Ok(status.success())
+}
+
+fn cargo() -> Command {
+    Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+}
+
+/// Run the day and its tests, then again each time one of its files changes. Only returns on
+/// error.
+pub fn watch(day: u8, dirs: &InputDirs) -> Result<()> {
+    let module = format!("src/day{day:02}.rs");
+    if !Path::new(&module).exists() {
+        bail!("No {module}: watch must be run from the root of the crate, on an existing day");
+    }
+
+    let mut snapshot = Snapshot::take(&watched_files(day, dirs));
+    let mut previous = None;
+    loop {
+        println!("=== Day {day:02}");
+        match run_day(day)? {
+            Some(answers) => {
+                for line in describe_changes(previous.as_ref(), &answers) {
+                    println!("{line}");
+                }
+                previous = Some(answers);
+            }
+            None => println!("Build failed"),
+        }
+        match test_day(day)? {
+            true => println!("Tests passed"),
+            false => println!("Tests failed, run `cargo test day{day:02}` for details"),
+        }
+        println!("Watching {} file(s) for changes...", snapshot.0.len());
+
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            let files = watched_files(day, dirs);
+            let current = Snapshot::take(&files);
+            let changes = current.changes(&snapshot);
+            if !changes.is_empty() {
+                let changes = changes
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>();
+                println!("\nChanged: {}", changes.join(", "));
+                // Let the editor finish writing before running
+                std::thread::sleep(POLL_INTERVAL);
+                snapshot = Snapshot::take(&files);
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [dir.path().join("day09.rs"), dir.path().join("day09.txt")];
+        std::fs::write(&files[0], "").unwrap();
+
+        let before = Snapshot::take(&files);
+        assert!(Snapshot::take(&files).changes(&before).is_empty());
+
+        std::fs::write(&files[1], "R 4").unwrap();
+        let after = Snapshot::take(&files);
+        assert_eq!(after.changes(&before), [files[1].as_path()]);
+
+        std::fs::remove_file(&files[0]).unwrap();
+        assert_eq!(Snapshot::take(&files).changes(&after), [files[0].as_path()]);
+    }
+
+    #[test]
+    fn test_describe_changes() {
+        let first = parse_answers(
+            "   Compiling aoc2022\n\
+             {\"day\":9,\"part\":1,\"answer\":13,\"type\":\"integer\"}\n\
+             {\"day\":9,\"part\":2,\"answer\":null,\"error\":\"No knots\"}\n",
+        );
+        assert_eq!(
+            describe_changes(None, &first),
+            ["Part 1: 13", "Part 2: error: No knots"]
+        );
+
+        let second = parse_answers(
+            "{\"day\":9,\"part\":1,\"answer\":13,\"type\":\"integer\"}\n\
+             {\"day\":9,\"part\":2,\"answer\":1,\"type\":\"integer\"}\n",
+        );
+        assert_eq!(
+            describe_changes(Some(&first), &second),
+            ["Part 1: 13 (unchanged)", "Part 2: 1 (was error: No knots)"]
+        );
+
+        let failed = parse_answers("{\"day\":9,\"part\":null,\"error\":\"Bad input\"}");
+        assert_eq!(
+            describe_changes(Some(&second), &failed),
+            [
+                "Part 1: error: Bad input (was 13)",
+                "Part 2: error: Bad input (was 1)"
+            ]
+        );
+        assert_eq!(
+            describe_changes(Some(&Answers::new()), &second),
+            ["Part 1: 13 (new)", "Part 2: 1 (new)"]
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use eyre::{bail, Result};
+
+use crate::{
+    parse::{Line, Lines, ParseError},
+    solution::{Answer, Solution},
+};
+
+pub struct Day{{DAY}};
+
+impl Solution for Day{{DAY}} {
+    const DAY: u8 = {{DAY_NUMBER}};
+
+    type Input = Vec<String>;
+
+    fn parse(data: &str) -> Result<Self::Input> {
+        Ok(Lines::new(Self::DAY, data)
+            .map(|line| parse_line(&line))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part1(_input: &Self::Input) -> Result<Answer> {
+        bail!("Part 1 is not solved yet")
+    }
+
+    fn part2(_input: &Self::Input) -> Result<Answer> {
+        bail!("Part 2 is not solved yet")
+    }
+}
+
+fn parse_line(line: &Line) -> Result<String, ParseError> {
+    Ok(line.scanner().take_rest().to_string())
+}
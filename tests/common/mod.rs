@@ -0,0 +1,93 @@
+//! A local stand-in for the Advent of Code website, answering requests with canned responses
+
+// Each test crate only uses some of the helpers
+#![allow(dead_code)]
+
+use std::{
+    io::Cursor,
+    sync::{Arc, Mutex},
+    thread::JoinHandle,
+};
+
+use tiny_http::{Header, Response, Server};
+
+pub type Reply = Response<Cursor<Vec<u8>>>;
+
+/// A request received by the server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Received {
+    pub method: String,
+    /// Path of the request, without the base path
+    pub path: String,
+    pub cookie: Option<String>,
+    pub body: String,
+}
+
+/// Serves the event under `/2022` on a random local port until dropped
+pub struct StubServer {
+    pub base_url: String,
+    server: Arc<Server>,
+    received: Arc<Mutex<Vec<Received>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl StubServer {
+    pub fn start(handler: impl Fn(&Received) -> Reply + Send + 'static) -> Self {
+        let server = Arc::new(Server::http("127.0.0.1:0").unwrap());
+        let base_url = format!("http://{}/2022", server.server_addr().to_ip().unwrap());
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        let thread = {
+            let server = Arc::clone(&server);
+            let received = Arc::clone(&received);
+            std::thread::spawn(move || {
+                for mut request in server.incoming_requests() {
+                    let mut body = String::new();
+                    request.as_reader().read_to_string(&mut body).unwrap();
+                    let req = Received {
+                        method: request.method().to_string(),
+                        path: request.url().trim_start_matches("/2022").to_string(),
+                        cookie: request
+                            .headers()
+                            .iter()
+                            .find(|h| h.field.equiv("Cookie"))
+                            .map(|h| h.value.to_string()),
+                        body,
+                    };
+                    let reply = handler(&req);
+                    received.lock().unwrap().push(req);
+                    request.respond(reply).unwrap();
+                }
+            })
+        };
+
+        Self {
+            base_url,
+            server,
+            received,
+            thread: Some(thread),
+        }
+    }
+
+    /// The requests received so far
+    pub fn received(&self) -> Vec<Received> {
+        self.received.lock().unwrap().clone()
+    }
+}
+
+impl Drop for StubServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+pub fn reply(status: u16, body: &str) -> Reply {
+    Response::from_string(body).with_status_code(status)
+}
+
+pub fn with_header(reply: Reply, field: &str, value: &str) -> Reply {
+    reply.with_header(Header::from_bytes(field.as_bytes(), value.as_bytes()).unwrap())
+}
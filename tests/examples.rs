@@ -0,0 +1,28 @@
+//! Checks the answers to the puzzle examples in `examples/`. The tests themselves are generated by
+//! `build.rs` from `examples/answers.toml`.
+
+use std::path::Path;
+
+use aoc2022::{answers::ExpectedAnswers, runner, solution::Part};
+
+fn check_example(name: &str, part: Part) {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let expected = ExpectedAnswers::load(root.join(aoc2022::answers::EXAMPLE_ANSWERS_FILE))
+        .unwrap()
+        .get_named(name, part)
+        .cloned()
+        .unwrap();
+
+    let day = name
+        .strip_prefix("day")
+        .and_then(|s| s.get(..2))
+        .and_then(|s| s.parse::<u8>().ok())
+        .unwrap_or_else(|| panic!("Example {name} should be named dayNN[-suffix]"));
+    let solution = aoc2022::find_day(day).unwrap_or_else(|| panic!("Day {day} doesn't exist"));
+    let data = std::fs::read_to_string(root.join("examples").join(format!("{name}.txt"))).unwrap();
+
+    let result = runner::run_day(solution, &data, Some(part)).unwrap();
+    assert_eq!(result.parts[0].answer, expected);
+}
+
+include!(concat!(env!("OUT_DIR"), "/example_tests.rs"));
@@ -0,0 +1,107 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use aoc2022::{
+    fetch::{fetch_input, Client, Fetched},
+    input::InputDirs,
+};
+
+mod common;
+
+use common::{reply, with_header, StubServer};
+
+fn dirs(dir: &tempfile::TempDir) -> InputDirs {
+    InputDirs {
+        env_dir: Some(dir.path().to_path_buf()),
+        local_dir: PathBuf::from("inputs"),
+        user_dir: None,
+    }
+}
+
+fn client(server: &StubServer) -> Client {
+    Client::new(&server.base_url, "secret").with_min_interval(Duration::ZERO)
+}
+
+#[test]
+fn test_downloads_and_caches() {
+    let server = StubServer::start(|req| reply(200, &format!("input of {}\n", req.path)));
+    let dir = tempfile::tempdir().unwrap();
+    let dirs = dirs(&dir);
+    let mut client = client(&server);
+
+    let path = dir.path().join("day07.txt");
+    assert_eq!(
+        fetch_input(&mut client, &dirs, 7).unwrap(),
+        Fetched::Downloaded(path.clone())
+    );
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "input of /day/7/input\n"
+    );
+    let received = server.received();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].method, "GET");
+    assert_eq!(received[0].cookie.as_deref(), Some("session=secret"));
+
+    // Never downloaded again
+    assert_eq!(
+        fetch_input(&mut client, &dirs, 7).unwrap(),
+        Fetched::Cached(path)
+    );
+    assert_eq!(server.received().len(), 1);
+}
+
+#[test]
+fn test_replaces_empty_placeholder() {
+    let server = StubServer::start(|_| reply(200, "42\n"));
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("day14.txt");
+    std::fs::write(&path, "").unwrap();
+
+    let result = fetch_input(&mut client(&server), &dirs(&dir), 14).unwrap();
+    assert_eq!(result, Fetched::Downloaded(path.clone()));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "42\n");
+}
+
+#[test]
+fn test_errors() {
+    let server = StubServer::start(|req| match req.path.as_str() {
+        "/day/1/input" => reply(
+            400,
+            "Puzzle inputs differ by user.  Please log in to get your puzzle input.",
+        ),
+        "/day/2/input" => with_header(reply(429, ""), "Retry-After", "60"),
+        _ => reply(
+            404,
+            "Please don't repeatedly request this endpoint before it unlocks!",
+        ),
+    });
+    let dir = tempfile::tempdir().unwrap();
+    let dirs = dirs(&dir);
+    let mut client = client(&server);
+
+    let mut error = |day| format!("{:#}", fetch_input(&mut client, &dirs, day).unwrap_err());
+    assert!(error(1).contains("rejected the session token (status 400)"));
+    let rate_limited = error(2);
+    assert!(rate_limited.contains("Too many requests to http://127.0.0.1:"));
+    assert!(rate_limited.ends_with("/2022/day/2/input, retry after 60s"));
+    assert!(error(25).contains("/2022/day/25/input was not found"));
+    assert!(!dir.path().join("day25.txt").exists());
+}
+
+#[test]
+fn test_rate_limit() {
+    let server = StubServer::start(|_| reply(200, "1\n"));
+    let dir = tempfile::tempdir().unwrap();
+    let dirs = dirs(&dir);
+    let mut client = client(&server).with_min_interval(Duration::from_millis(200));
+
+    let start = Instant::now();
+    for day in 1..=3 {
+        fetch_input(&mut client, &dirs, day).unwrap();
+    }
+    assert!(start.elapsed() >= Duration::from_millis(400));
+    assert_eq!(server.received().len(), 3);
+}
@@ -0,0 +1,271 @@
+//! Property tests of the parsers: models rendered to puzzle text must parse back to the same
+//! model, and no input, however broken, may make a parser panic
+
+use std::collections::{BTreeMap, VecDeque};
+
+use itertools::Itertools;
+use proptest::{prelude::*, sample::Index};
+
+use aoc2022::{
+    day05::{self, Day05, Stacks},
+    day07::Day07,
+    day09::{self, Day09},
+    day10::{Day10, Inst},
+    day11::{DivisibilityTest, Item, Monkey, Monkeys, Operation, Val},
+    day13::{self, Value},
+    geometry::Dir,
+    solution::Solution,
+    DAYS,
+};
+
+fn stacks_and_moves() -> impl Strategy<Value = (Vec<Vec<u8>>, Vec<day05::Move>)> {
+    (1..=9usize).prop_flat_map(|num_stacks| {
+        let stacks = prop::collection::vec(prop::collection::vec(b'A'..=b'Z', 0..8), num_stacks);
+        let moves = prop::collection::vec(
+            (0..20usize, 1..=num_stacks, 1..=num_stacks)
+                .prop_map(|(num, from, to)| day05::Move(num, from, to)),
+            0..20,
+        );
+        (stacks, moves)
+    })
+}
+
+/// A file system as listed by `ls`: files with their size, and directories
+#[derive(Debug, Clone)]
+enum Node {
+    File(usize),
+    Dir(BTreeMap<String, Node>),
+}
+
+fn file_system() -> impl Strategy<Value = BTreeMap<String, Node>> {
+    let leaf = (1..300_000usize).prop_map(Node::File);
+    let node = leaf.prop_recursive(4, 32, 5, |inner| {
+        prop::collection::btree_map("[a-z]{1,8}", inner, 0..5).prop_map(Node::Dir)
+    });
+    prop::collection::btree_map("[a-z]{1,8}", node, 0..5)
+}
+
+/// Explore the file system in a terminal session, descending into every directory
+fn render_session(root: &BTreeMap<String, Node>) -> String {
+    fn explore(entries: &BTreeMap<String, Node>, text: &mut String) {
+        *text += "$ ls\n";
+        for (name, node) in entries {
+            match node {
+                Node::File(size) => *text += &format!("{size} {name}\n"),
+                Node::Dir(_) => *text += &format!("dir {name}\n"),
+            }
+        }
+        for (name, node) in entries {
+            if let Node::Dir(entries) = node {
+                *text += &format!("$ cd {name}\n");
+                explore(entries, text);
+                *text += "$ cd ..\n";
+            }
+        }
+    }
+
+    let mut text = "$ cd /\n".to_string();
+    explore(root, &mut text);
+    text
+}
+
+/// The size of every directory, in the order the shell computes them: the subdirectories come
+/// before their parent, the root last
+fn dir_sizes(name: &str, entries: &BTreeMap<String, Node>, sizes: &mut Vec<(String, usize)>) {
+    let mut total = 0;
+    for (name, node) in entries {
+        match node {
+            Node::File(size) => total += size,
+            Node::Dir(entries) => {
+                dir_sizes(name, entries, sizes);
+                total += sizes.last().unwrap().1;
+            }
+        }
+    }
+    sizes.push((name.to_string(), total));
+}
+
+fn dir() -> impl Strategy<Value = Dir> {
+    prop_oneof![
+        Just(Dir::Up),
+        Just(Dir::Down),
+        Just(Dir::Left),
+        Just(Dir::Right)
+    ]
+}
+
+fn render_dir(dir: Dir) -> &'static str {
+    match dir {
+        Dir::Up => "U",
+        Dir::Down => "D",
+        Dir::Left => "L",
+        Dir::Right => "R",
+    }
+}
+
+fn val() -> impl Strategy<Value = Val> {
+    prop_oneof![Just(Val::Old), (0..100usize).prop_map(Val::Literal)]
+}
+
+fn monkeys() -> impl Strategy<Value = Vec<Monkey>> {
+    (2..=8usize).prop_flat_map(|num_monkeys| {
+        // A monkey never throws to itself
+        let target =
+            move |id: usize| (0..num_monkeys - 1).prop_map(move |t| t + (t >= id) as usize);
+        let monkeys = (0..num_monkeys).map(move |id| {
+            let items = prop::collection::vec_deque((0..100usize).prop_map(Item), 0..6);
+            let operation = (any::<bool>(), val(), val()).prop_map(|(add, lhs, rhs)| match add {
+                true => Operation::Add(lhs, rhs),
+                false => Operation::Mult(lhs, rhs),
+            });
+            (items, operation, 1..30usize, target(id), target(id)).prop_map(
+                move |(items, operation, div, if_true, if_false)| Monkey {
+                    id,
+                    starting_items: items,
+                    operation,
+                    div_test: DivisibilityTest(div),
+                    throw_to_if_true: if_true,
+                    throw_to_if_false: if_false,
+                    num_inspections: 0,
+                },
+            )
+        });
+        monkeys.collect::<Vec<_>>()
+    })
+}
+
+fn packet() -> impl Strategy<Value = Value> {
+    let value = any::<u32>()
+        .prop_map(Value::Integer)
+        .prop_recursive(4, 32, 5, |inner| {
+            prop::collection::vec(inner, 0..5).prop_map(Value::List)
+        });
+    prop::collection::vec(value, 0..5).prop_map(Value::List)
+}
+
+fn render_packet(value: &Value) -> String {
+    match value {
+        Value::Integer(v) => v.to_string(),
+        Value::List(values) => format!("[{}]", values.iter().map(render_packet).format(",")),
+    }
+}
+
+/// The example inputs, whose broken versions are good candidates to find bugs in the parsers
+fn examples() -> Vec<(usize, String)> {
+    DAYS.iter()
+        .enumerate()
+        .map(|(i, solution)| {
+            let path = format!(
+                "{}/examples/day{:02}.txt",
+                env!("CARGO_MANIFEST_DIR"),
+                solution.day()
+            );
+            (i, std::fs::read_to_string(path).unwrap())
+        })
+        .collect()
+}
+
+proptest! {
+    #[test]
+    fn roundtrip_day05((stacks, moves) in stacks_and_moves()) {
+        let text = day05::render_procedure(&Stacks::from(stacks.clone()), &moves);
+        let (parsed, parsed_moves) = Day05::parse(&text).unwrap();
+        prop_assert_eq!(parsed.len(), stacks.len());
+        for (i, stack) in stacks.iter().enumerate() {
+            prop_assert_eq!(parsed.stack(i + 1), Some(&VecDeque::from(stack.clone())));
+        }
+        prop_assert_eq!(parsed_moves, moves);
+    }
+
+    #[test]
+    fn roundtrip_day07(root in file_system()) {
+        let shell = Day07::parse(&render_session(&root)).unwrap();
+        let mut sizes = Vec::new();
+        dir_sizes("/", &root, &mut sizes);
+        prop_assert_eq!(shell.compute_dir_sizes().unwrap(), sizes);
+    }
+
+    #[test]
+    fn roundtrip_day09(moves in prop::collection::vec((dir(), 0..100usize), 0..50)) {
+        let text = moves
+            .iter()
+            .map(|(dir, num)| format!("{} {num}\n", render_dir(*dir)))
+            .collect::<String>();
+        let parsed = Day09::parse(&text).unwrap();
+        let expected = moves.iter().map(|(dir, num)| day09::Move(*dir, *num)).collect::<Vec<_>>();
+        prop_assert_eq!(parsed.0, expected);
+    }
+
+    #[test]
+    fn roundtrip_day10(
+        program in prop::collection::vec(
+            prop_oneof![Just(Inst::Noop), any::<isize>().prop_map(Inst::AddX)],
+            0..50,
+        )
+    ) {
+        let text = program
+            .iter()
+            .map(|inst| match inst {
+                Inst::Noop => "noop\n".to_string(),
+                Inst::AddX(v) => format!("addx {v}\n"),
+            })
+            .collect::<String>();
+        prop_assert_eq!(Day10::parse(&text).unwrap(), program);
+    }
+
+    #[test]
+    fn roundtrip_day11(monkeys in monkeys()) {
+        let text = monkeys.iter().join("\n");
+        let parsed = text.parse::<Monkeys>().unwrap();
+        prop_assert_eq!(parsed.0, monkeys);
+    }
+
+    #[test]
+    fn roundtrip_day13(pairs in prop::collection::vec((packet(), packet()), 1..10)) {
+        let text = pairs
+            .iter()
+            .map(|(p1, p2)| format!("{}\n{}\n", render_packet(p1), render_packet(p2)))
+            .join("\n");
+        prop_assert_eq!(day13::parse_packets(&text).unwrap(), pairs);
+    }
+
+    #[test]
+    fn parsers_dont_panic(data in "[\\[\\]0-9a-zA-Z ,:$./=+*\n-]{0,200}") {
+        for solution in DAYS {
+            let _ = solution.parse(&data);
+        }
+    }
+
+    #[test]
+    fn parsers_dont_panic_on_broken_examples(
+        example in prop::sample::select(examples()),
+        edits in prop::collection::vec((any::<Index>(), "[\\[\\]0-9a-z ,:$\n-]?"), 1..4),
+    ) {
+        let (i, mut data) = example;
+        for (index, replacement) in edits {
+            let chars = data.chars().collect::<Vec<_>>();
+            if chars.is_empty() {
+                break;
+            }
+            let at = index.index(chars.len());
+            data = chars[..at]
+                .iter()
+                .chain(replacement.chars().collect::<Vec<_>>().iter())
+                .chain(&chars[at + 1..])
+                .collect();
+        }
+        let _ = DAYS[i].parse(&data);
+    }
+}
+
+/// Deeply nested or very long inputs, too large for the property tests above
+#[test]
+fn parsers_dont_overflow_the_stack() {
+    let nested = format!("{}{}\n", "[".repeat(100_000), "]".repeat(100_000));
+    let long = "1,".repeat(100_000);
+    for data in [nested, long] {
+        for solution in DAYS {
+            let _ = solution.parse(&data);
+        }
+    }
+}
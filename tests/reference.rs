@@ -0,0 +1,94 @@
+//! Differential tests comparing the solutions with the naive ones of the `reference` module, on
+//! generated inputs and on the real ones when present. Run with `cargo test --features reference`.
+
+use aoc2022::{
+    find_day,
+    generate::{self, Params},
+    input::InputDirs,
+    reference, runner,
+    solution::{DynSolution, Part},
+};
+
+const SEEDS: u64 = 8;
+
+/// Parameters giving inputs of various sizes, small enough for the naive solutions
+fn params(day: u8, seed: u64) -> Params {
+    let size = match day {
+        11 => 2 + seed % 8,
+        _ => 1 + seed * 10,
+    };
+    let seed_size = seed as usize;
+    let (width, height) = match day {
+        5 => (Some(1 + seed_size % 9), None),
+        8 | 12 => (Some(20 + 5 * seed_size), Some(7 + 3 * seed_size)),
+        _ => (None, None),
+    };
+    Params {
+        seed,
+        size: Some(size as usize),
+        width,
+        height,
+        depth: Some(1 + seed_size % 5),
+        ..Params::default()
+    }
+}
+
+/// Describe the parts where the solution and the reference disagree
+fn disagreements(solution: &dyn DynSolution, data: &str, origin: &str) -> Vec<String> {
+    let day = solution.day();
+    let result = runner::run_day(solution, data, None);
+    Part::ALL
+        .iter()
+        .filter_map(|&part| {
+            let answer = match &result {
+                Ok(result) => match result.part(part) {
+                    Some(p) => Ok(p.answer.clone()),
+                    None => Err(result
+                        .failures
+                        .iter()
+                        .find(|(p, _)| *p == part)
+                        .map_or("no answer".to_string(), |(_, e)| format!("{e:#}"))),
+                },
+                Err(e) => Err(format!("{e:#}")),
+            };
+            let expected = reference::solve(day, data, part).map_err(|e| format!("{e:#}"));
+            (answer != expected).then(|| {
+                format!("Day {day} part {part} on {origin}: {answer:?} instead of {expected:?}")
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn test_generated_inputs() {
+    let mut failures = Vec::new();
+    let days = reference::DAYS
+        .into_iter()
+        .filter(|d| generate::DAYS.contains(d));
+    for day in days {
+        let solution = find_day(day).unwrap();
+        for seed in 0..SEEDS {
+            let data = generate::generate(day, &params(day, seed)).unwrap();
+            let origin = format!("the input generated with seed {seed}");
+            failures.extend(disagreements(solution, &data, &origin));
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn test_real_inputs() {
+    let dirs = InputDirs::from_env();
+    let mut failures = Vec::new();
+    for day in reference::DAYS {
+        // The placeholder of a day that was just scaffolded is empty
+        match dirs.read(day) {
+            Ok(data) if !data.trim().is_empty() => {
+                let solution = find_day(day).unwrap();
+                failures.extend(disagreements(solution, &data, "the real input"));
+            }
+            _ => {}
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use aoc2022::{
+    fetch::Client,
+    solution::{Answer, Part},
+    submit::{submit, Feedback, History},
+};
+
+mod common;
+
+use common::{reply, StubServer};
+
+fn client(server: &StubServer) -> Client {
+    Client::new(&server.base_url, "secret").with_min_interval(Duration::ZERO)
+}
+
+fn page(text: &str) -> String {
+    format!("<html><body><main><article><p>{text}</p></article></main></body></html>")
+}
+
+/// Judges the answers to day 1 part 1, whose answer is 42
+fn judge(body: &str) -> String {
+    let answer = body
+        .split('&')
+        .find_map(|field| field.strip_prefix("answer="))
+        .and_then(|a| a.parse::<i64>().ok());
+    match answer {
+        Some(42) => page("That's the right answer! You are <em>one gold star</em> closer."),
+        Some(a) if a > 42 => page("That's not the right answer; your answer is too high."),
+        Some(_) => page("That's not the right answer; your answer is too low."),
+        None => page("That's not the right answer."),
+    }
+}
+
+#[test]
+fn test_submit() {
+    let server = StubServer::start(|req| reply(200, &judge(&req.body)));
+    let mut client = client(&server);
+    let mut history = History::default();
+    let mut submit = |answer: i64| submit(&mut client, &mut history, 1, Part::One, &answer.into());
+
+    assert_eq!(submit(100).unwrap(), Feedback::TooHigh);
+    assert_eq!(submit(10).unwrap(), Feedback::TooLow);
+    // Known to be wrong, so not even sent
+    assert!(submit(100).is_err());
+    assert!(submit(200).is_err());
+    assert!(submit(5).is_err());
+    assert_eq!(submit(42).unwrap(), Feedback::Correct);
+    assert!(submit(43).is_err());
+
+    let received = server.received();
+    assert_eq!(received.len(), 3);
+    assert_eq!(received[0].method, "POST");
+    assert_eq!(received[0].path, "/day/1/answer");
+    assert_eq!(received[0].cookie.as_deref(), Some("session=secret"));
+    assert_eq!(received[0].body, "level=1&answer=100");
+
+    let feedbacks = history
+        .attempts
+        .iter()
+        .map(|a| (a.answer.clone(), a.feedback))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        feedbacks,
+        [
+            (Answer::Integer(100), Feedback::TooHigh),
+            (Answer::Integer(10), Feedback::TooLow),
+            (Answer::Integer(42), Feedback::Correct),
+        ]
+    );
+}
+
+#[test]
+fn test_history_file() {
+    let server = StubServer::start(|req| reply(200, &judge(&req.body)));
+    let mut client = client(&server);
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("submissions.toml");
+
+    let mut history = History::load(&path).unwrap();
+    assert!(history.attempts.is_empty());
+    submit(&mut client, &mut history, 1, Part::One, &"ABC".into()).unwrap();
+    history.save(&path).unwrap();
+
+    let mut history = History::load(&path).unwrap();
+    assert!(submit(&mut client, &mut history, 1, Part::One, &"ABC".into()).is_err());
+    assert_eq!(server.received().len(), 1);
+}
+
+#[test]
+fn test_unjudged_responses() {
+    let server = StubServer::start(|req| match req.body.as_str() {
+        "level=1&answer=1" => reply(
+            200,
+            &page("You gave an answer too recently; you have 38s left to wait."),
+        ),
+        "level=2&answer=1" => reply(
+            200,
+            &page("You don't seem to be solving the right level. Did you already complete it?"),
+        ),
+        _ => reply(200, "<html>Something else</html>"),
+    });
+    let mut client = client(&server);
+    let mut history = History::default();
+
+    let err = submit(&mut client, &mut history, 1, Part::One, &1.into()).unwrap_err();
+    assert!(err.to_string().contains("38s"), "{err}");
+    // Not judged, so it can be submitted again
+    assert!(submit(&mut client, &mut history, 1, Part::One, &1.into()).is_err());
+    assert!(submit(&mut client, &mut history, 1, Part::Two, &1.into()).is_err());
+    assert!(submit(&mut client, &mut history, 1, Part::Two, &2.into()).is_err());
+    assert!(history.attempts.is_empty());
+    assert_eq!(server.received().len(), 4);
+}